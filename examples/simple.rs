@@ -27,14 +27,14 @@ fn main() -> io::Result<()> {
 
     loop {
         match event_reader.read_events()? {
-            Event::Key(key, _key_code) => match key {
+            Event::Key(key, _key_code, info) if info.is_press() => match key {
                 // Exit after pressing on 'q'
                 Key(_, KeyCode::Char('q')) => break,
                 // Increase the number when Ctrl + Right was pressed
-                Key(KeyMod::Ctrl, KeyCode::Right) =>
+                Key(KeyMod::CTRL, KeyCode::Right) =>
                     number += 1,
                 // Decrease the number when Ctrl + Left was pressed
-                Key(KeyMod::Ctrl, KeyCode::Left) =>
+                Key(KeyMod::CTRL, KeyCode::Left) =>
                     number -= 1,
                 _ => ()
             },
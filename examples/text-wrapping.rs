@@ -28,8 +28,8 @@ fn main() -> io::Result<()> {
 
     loop {
         match event_reader.read_events()? {
-            Event::Key(_, KeyCode::Char('q')) => break,
-            Event::Key(_, KeyCode::Char('w')) => {
+            Event::Key(_, KeyCode::Char('q'), info) if info.is_press() => break,
+            Event::Key(_, KeyCode::Char('w'), info) if info.is_press() => {
                 state.text_wrap = match state.text_wrap {
                     Wrap::None => Wrap::Break,
                     Wrap::Break => Wrap::Words,
@@ -37,7 +37,7 @@ fn main() -> io::Result<()> {
                     Wrap::BreakWords => Wrap::None
                 }
             },
-            Event::Key(_, KeyCode::Char('a')) => {
+            Event::Key(_, KeyCode::Char('a'), info) if info.is_press() => {
                 state.text_align = match state.text_align {
                     Align::Start => Align::Center,
                     Align::Center => Align::End,
@@ -1,6 +1,6 @@
 use std::ops::Not;
 
-use tuich::{buffer::Buffer, event::{Event, Key, KeyCode}, layout::{Align, Clip, Rect}, style::{BorderKind, Color}, text::Text, widget::{prompt::PromptState, Block, Draw, Prompt, RefDraw}};
+use tuich::{buffer::Buffer, event::{Event, FocusManager, Key, KeyCode}, layout::{Align, Clip, Rect}, style::{BorderKind, Color}, text::Text, widget::{prompt::PromptState, Block, Draw, Prompt, RefDraw}};
 
 use crate::{state::{Page, State, Todo}, widget::todo::TodoList, Msg};
 
@@ -11,31 +11,56 @@ pub enum AppMsg {
     None
 }
 
+/// New todo page field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Desc
+}
+
 /// App
 #[derive(Debug)]
 pub struct App {
     pub state: State,
+    /// Terminal area
+    pub area: Rect,
 
     pub todo_title_prompt: PromptState,
     pub todo_desc_prompt: PromptState,
-    pub is_desc_focused: bool,
+    pub focus: FocusManager<Field>,
 }
 impl App {
-    pub fn new() -> Self {
+    pub fn new(area: Rect) -> Self {
+        let mut state = State::new();
+        state.list.set_viewport(todos_list_rect(area).height);
+
         Self {
-            state: State::new(),
+            state,
+            area,
             todo_title_prompt: PromptState::default(),
             todo_desc_prompt: PromptState::default(),
-            is_desc_focused: false
+            focus: FocusManager::new()
+                .item(Field::Title)
+                .item(Field::Desc)
         }
     }
 
     //
 
     pub fn handle_events(&mut self, event: Event) -> Msg {
+        if self.state.page == Page::Todos
+            && self.state.list.handle_event(&event, todos_list_rect(self.area))
+        {
+            return Msg::Draw;
+        }
+
         match event {
-            Event::Key(key, _)  => self.handle_keys(key),
-            Event::Resize(w, h) => Msg::Resize(w, h),
+            Event::Key(key, _, info) if info.is_press() => self.handle_keys(key),
+            Event::Resize(width, height) => {
+                self.area = Rect::sized(width, height);
+                self.state.list.set_viewport(todos_list_rect(self.area).height);
+                Msg::Draw
+            },
             _                   => Msg::None
         }
     }
@@ -49,8 +74,6 @@ impl App {
     fn todos_handle_key(&mut self, key: Key) -> Msg {
         match key.code() {
             KeyCode::Char('q') => Msg::Quit,
-            KeyCode::Char('j') => self.select_next_todo(),
-            KeyCode::Char('k') => self.select_prev_todo(),
 
             KeyCode::Char('d') |
             KeyCode::Backspace => self.remove_cur_todo(),
@@ -67,18 +90,12 @@ impl App {
     fn new_todo_handle_key(&mut self, key: Key) -> Msg {
         match key {
             Key(_, KeyCode::Esc) => self.goto_page(Page::Todos),
-            Key(_, KeyCode::Tab) => {
-                self.is_desc_focused = !self.is_desc_focused;
-                Msg::Draw
-            },
             Key(_, KeyCode::Enter) => self.add_todo(),
 
-            key => {
-                if self.is_desc_focused {
-                    self.todo_desc_prompt.handle_keys(key).into()
-                } else {
-                    self.todo_title_prompt.handle_keys(key).into()
-                }
+            key if self.focus.handle_key(key) => Msg::Draw,
+            key => match self.focus.focused() {
+                Some(Field::Desc) => self.todo_desc_prompt.handle_keys(key).into(),
+                _ => self.todo_title_prompt.handle_keys(key).into()
             }
         }
     }
@@ -104,7 +121,7 @@ impl App {
             false
         ));
 
-        self.is_desc_focused = false;
+        self.focus.focus_first();
         self.todo_title_prompt.clear();
         self.todo_desc_prompt.clear();
 
@@ -112,28 +129,36 @@ impl App {
     }
 
     fn toggle_cur_todo(&mut self) -> Msg {
-        self.state.get_cur_todo_mut().toggle();
-        Msg::Draw
+        match self.state.get_cur_todo_mut() {
+            Some(todo) => {
+                todo.toggle();
+                Msg::Draw
+            },
+            None => Msg::None
+        }
     }
     fn remove_cur_todo(&mut self) -> Msg {
-        self.state.remove_todo(self.state.cur_todo);
-        Msg::Draw
-    }
-    fn select_next_todo(&mut self) -> Msg {
-        self.state.select_next_todo(1);
-        Msg::Draw
-    }
-    fn select_prev_todo(&mut self) -> Msg {
-        self.state.select_prev_todo(1);
-        Msg::Draw
+        match self.state.list.selected() {
+            Some(index) => self.state.remove_todo(index).into(),
+            None => Msg::None
+        }
     }
 }
 
+/// Rect of the app in the terminal `area`
+fn app_rect(area: Rect) -> Rect {
+    Rect::sized(60, 24)
+        .min_size(area.size())
+        .align_center(area)
+}
+/// Rect of the todo list in the terminal `area`
+fn todos_list_rect(area: Rect) -> Rect {
+    app_rect(area).margin(1)
+}
+
 impl RefDraw for App {
     fn draw(&self, buf: &mut Buffer, rect: Rect) -> Rect {
-        let rect = Rect::sized(60, 24)
-            .min_size(rect.size())
-            .align_center(rect);
+        let rect = app_rect(rect);
 
         match self.state.page {
             Page::Todos => draw_todos_page(self, buf, rect),
@@ -145,7 +170,7 @@ impl RefDraw for App {
 }
 
 fn draw_todos_page(app: &App, buf: &mut Buffer, rect: Rect) {
-    let borders_rect = Block::default()
+    Block::default()
         .title(
             Text::new(" TODOS ", Color::Green)
                 .align(Align::Center)
@@ -155,11 +180,10 @@ fn draw_todos_page(app: &App, buf: &mut Buffer, rect: Rect) {
                 .clip(Clip::Ellipsis)
         )
         .style(Color::LightBlack)
-        .draw(buf, rect)
-        .margin(1);
+        .draw(buf, rect);
 
-    TodoList(&app.state.todos, app.state.cur_todo)
-        .draw(buf, borders_rect);
+    TodoList(&app.state.todos, &app.state.list)
+        .draw(buf, rect.margin(1));
 }
 
 fn draw_new_todo_page(app: &App, buf: &mut Buffer, rect: Rect) {
@@ -194,7 +218,7 @@ fn draw_new_todo_page(app: &App, buf: &mut Buffer, rect: Rect) {
     Prompt::new(&app.todo_title_prompt)
         .border_kind(BorderKind::Single)
         .border_style(Color::Gray)
-        .focused(!app.is_desc_focused)
+        .focused(app.focus.is_focused(&Field::Title))
         .draw(buf, borders_rect.add_y(1).with_height(3));
 
     Text::new("Todo description (optional):", Color::Gray)
@@ -202,6 +226,6 @@ fn draw_new_todo_page(app: &App, buf: &mut Buffer, rect: Rect) {
     Prompt::new(&app.todo_desc_prompt)
         .border_kind(BorderKind::Single)
         .border_style(Color::Gray)
-        .focused(app.is_desc_focused)
+        .focused(app.focus.is_focused(&Field::Desc))
         .draw(buf, borders_rect.add_y(5).with_height(3));
 }
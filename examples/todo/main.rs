@@ -15,7 +15,6 @@ pub enum Msg {
     #[default]
     None,
     Draw,
-    Resize(u16, u16),
     Quit
 }
 impl From<bool> for Msg {
@@ -26,9 +25,11 @@ impl From<bool> for Msg {
 }
 
 fn main() -> io::Result<()> {
-    let mut term: Term = Terminal::classic(CrosstermBackend::default())?;
+    let mut term: Term = Terminal::classic(CrosstermBackend::default())?
+        .auto_resize(true);
+    term.enable_mouse()?;
     let mut event_reader = term.event_reader();
-    let mut app = App::new();
+    let mut app = App::new(term.rect());
 
     draw_ui(&mut term, &app)?;
 
@@ -39,7 +40,6 @@ fn main() -> io::Result<()> {
         match msg {
             Msg::None => continue,
             Msg::Draw => draw_ui(&mut term, &app)?,
-            Msg::Resize(w, h) => resize(&mut term, &app, w, h)?,
             Msg::Quit => break
         }
     }
@@ -48,15 +48,7 @@ fn main() -> io::Result<()> {
 }
 
 fn draw_ui(term: &mut Term, app: &App) -> io::Result<()> {
-    term.clear();
-
-    let rect = term.rect();
-    app.draw(&mut term.buffer, rect);
-
-    term.draw()?;
-    Ok(())
-}
-fn resize(term: &mut Term, app: &App, width: u16, height: u16) -> io::Result<()> {
-    term.resize(width, height)?;
-    draw_ui(term, app)
+    term.draw_with(|buf, rect| {
+        app.draw(buf, rect);
+    })
 }
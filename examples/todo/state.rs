@@ -1,3 +1,5 @@
+use tuich::widget::select_list::ListState;
+
 /// Todo
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Todo {
@@ -37,13 +39,11 @@ pub enum Page {
 pub struct State {
     pub page: Page,
     pub todos: Vec<Todo>,
-    pub cur_todo: usize,
+    pub list: ListState,
 }
 impl State {
     pub fn new() -> Self {
-        Self {
-            page: Page::default(),
-            todos: vec![
+        let todos = vec![
                 Todo::new::<_, String>("Im a todo without a description!", None, false),
                 Todo::new(
                     "I have a description",
@@ -77,8 +77,14 @@ impl State {
                     Some("Nor again is there anyone who loves or pursues or desires to obtain pain of itself, because it is pain"),
                     false
                 ),
-            ],
-            cur_todo: 0
+            ];
+
+        Self {
+            page: Page::default(),
+            list: ListState::new(todos.len())
+                .item_height(4)
+                .scroll_padding(1),
+            todos,
         }
     }
 
@@ -86,30 +92,21 @@ impl State {
 
     pub fn add_todo(&mut self, todo: Todo) {
         self.todos.insert(0, todo);
+        self.list.set_len(self.todos.len());
+        self.list.select(0);
     }
     pub fn remove_todo(&mut self, index: usize) -> bool {
         if index >= self.todos.len() {
             false
         } else {
             self.todos.remove(index);
-            self.clamp_cur_todo();
+            self.list.set_len(self.todos.len());
             true
         }
     }
 
-    pub fn select_next_todo(&mut self, jump: usize) {
-        self.cur_todo += jump;
-        self.clamp_cur_todo();
-    }
-    pub fn select_prev_todo(&mut self, jump: usize) {
-        self.cur_todo = self.cur_todo.saturating_sub(jump);
-    }
-    pub fn clamp_cur_todo(&mut self) {
-        self.cur_todo = self.cur_todo.min(self.todos.len().saturating_sub(1));
-    }
-
-    pub fn get_cur_todo_mut(&mut self) -> &mut Todo {
-        self.get_todo_mut(self.cur_todo).unwrap()
+    pub fn get_cur_todo_mut(&mut self) -> Option<&mut Todo> {
+        self.get_todo_mut(self.list.selected()?)
     }
 
     pub fn get_todo_mut(&mut self, index: usize) -> Option<&mut Todo> {
@@ -1,5 +1,8 @@
 use crate::{buffer::Buffer, style::Style};
 
+#[cfg(feature="backend-event")]
+use std::time::Duration;
+
 #[cfg(feature="backend-event")]
 use crate::event::{Event, Key, Mouse};
 
@@ -7,16 +10,31 @@ use crate::event::{Event, Key, Mouse};
 pub trait BackendEventReader {
     type EventError;
 
+    /// Wait for an event to become available
+    /// Blocks until the event is available
     fn read_events(&mut self) -> Result<Event, Self::EventError>;
-    fn read_keys(&mut self) -> Result<Option<Key>, Self::EventError> {
-        let event = self.read_events()?;
-
-        if let Event::Key(key, _) = event {
-            Ok(Some(key))
+    /// Check whether an event is available within the given `timeout`
+    /// Returns `true` if [BackendEventReader::read_events] won't block
+    fn poll(&mut self, timeout: Duration) -> Result<bool, Self::EventError>;
+    /// Read an event if it's immediately available
+    /// Never blocks
+    fn try_read(&mut self) -> Result<Option<Event>, Self::EventError> {
+        self.read_timeout(Duration::ZERO)
+    }
+    /// Wait for an event at most `timeout`
+    /// Returns `None` if no event was available in time
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, Self::EventError> {
+        if self.poll(timeout)? {
+            self.read_events().map(Some)
         } else {
             Ok(None)
         }
     }
+    /// Wait for an event and return it if it's a key press or repeat
+    /// Key releases are ignored
+    fn read_keys(&mut self) -> Result<Option<Key>, Self::EventError> {
+        Ok(self.read_events()?.key_press())
+    }
     fn read_mouse(&mut self) -> Result<Option<Mouse>, Self::EventError> {
         let event = self.read_events()?;
 
@@ -122,6 +140,18 @@ pub trait BackendEvent {
     fn event_reader(&self) -> Self::EventReader;
 }
 
+/// Backend event stream
+/// A backend with ability to read events asynchronously
+///
+/// # Notes
+///
+/// - the item type is up to the backend, the [crossterm](super::crossterm) one yields `io::Result<Event>`
+#[cfg(feature="async")]
+pub trait BackendEventStream {
+    type EventStream: futures_core::Stream;
+    fn event_stream(&self) -> Self::EventStream;
+}
+
 impl<T: BackendAltScreen + BackendRawMode> BackendClassicMode for T {
     fn enter_classic_mode(&mut self) -> Result<(), Self::Error> {
         self.enter_raw_mode()?;
@@ -130,3 +160,64 @@ impl<T: BackendAltScreen + BackendRawMode> BackendClassicMode for T {
         self.place_cursor(0, 0)
     }
 }
+
+
+// Tests
+#[cfg(feature="backend-event")]
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, time::Instant};
+
+    use super::*;
+
+    /// Reads events from a queue, waits the whole timeout when it's empty
+    #[derive(Default)]
+    struct QueueReader {
+        events: VecDeque<Event>,
+        polls: Vec<Duration>,
+    }
+    impl BackendEventReader for QueueReader {
+        type EventError = ();
+
+        fn read_events(&mut self) -> Result<Event, Self::EventError> {
+            self.events.pop_front().ok_or(())
+        }
+        fn poll(&mut self, timeout: Duration) -> Result<bool, Self::EventError> {
+            self.polls.push(timeout);
+
+            if self.events.is_empty() {
+                std::thread::sleep(timeout);
+            }
+            Ok(!self.events.is_empty())
+        }
+    }
+
+    #[test]
+    fn try_read() {
+        let mut reader = QueueReader {
+            events: VecDeque::from([Event::Focus]),
+            ..Default::default()
+        };
+
+        assert_eq!(reader.try_read(), Ok(Some(Event::Focus)));
+        assert_eq!(reader.try_read(), Ok(None));
+        assert_eq!(reader.polls, vec![Duration::ZERO; 2], "Never waits");
+        assert_eq!(reader.events.len(), 0);
+    }
+
+    #[test]
+    fn read_timeout() {
+        let timeout = Duration::from_millis(20);
+        let mut reader = QueueReader {
+            events: VecDeque::from([Event::Blur]),
+            ..Default::default()
+        };
+
+        assert_eq!(reader.read_timeout(timeout), Ok(Some(Event::Blur)));
+
+        let start = Instant::now();
+        assert_eq!(reader.read_timeout(timeout), Ok(None));
+        assert!(start.elapsed() >= timeout);
+        assert_eq!(reader.polls, vec![timeout; 2]);
+    }
+}
@@ -1,13 +1,27 @@
 //! Backend for the [crossterm](https://docs.rs/crossterm) lib!
+//!
+//! # Notes
+//!
+//! - [EventStream] (requires `async` feature) yields `io::Result<Event>`, not plain [Event],
+//!   read errors are passed through instead of ending the stream
+//! - [Terminal::draw_to_vec] returns a frame as bytes, to write it without blocking
 
-use std::io::{self, Write};
+use std::{io::{self, Write}, time::Duration};
 
 use crossterm::{cursor, event::{DisableMouseCapture, EnableMouseCapture}, execute, style::{Attribute, SetBackgroundColor, SetForegroundColor}, terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen}};
 
-use crate::{event::{Event, Key, KeyCode, KeyMod, ModKeyCode, Mouse, MouseBtn}, style::{Color, Style, UnderlineKind}};
+use crate::{event::{Event, Key, KeyCode, KeyInfo, KeyKind, KeyMod, KeyState, ModKeyCode, Mouse, MouseBtn}, style::{Color, Style, UnderlineKind}};
 
 use super::{Backend, BackendAltScreen, BackendEvent, BackendEventReader, BackendMouse, BackendRawMode};
 
+#[cfg(feature="async")]
+use std::{pin::Pin, task::{Context, Poll}};
+
+use crate::terminal::Terminal;
+
+#[cfg(feature="async")]
+use super::BackendEventStream;
+
 /// [Crossterm](https://docs.rs/crossterm) backend
 /// 
 /// # Implements
@@ -16,6 +30,7 @@ use super::{Backend, BackendAltScreen, BackendEvent, BackendEventReader, Backend
 /// - [BackendRawMode] - raw mode support
 /// - [BackendMouse] - mouse support
 /// - [BackendEvent] - custom events wrapper
+/// - [BackendEventStream] - async events stream (requires `async` feature)
 #[derive(Debug, Clone)]
 pub struct CrosstermBackend<W: Write>(pub W);
 impl<W: Write> Backend for CrosstermBackend<W> {
@@ -95,6 +110,17 @@ impl Default for CrosstermBackend<io::Stdout> {
         CrosstermBackend(io::stdout())
     }
 }
+impl CrosstermBackend<Vec<u8>> {
+    /// Create a [CrosstermBackend] that writes everything into memory
+    /// Use [CrosstermBackend::take] to get the written bytes
+    pub fn buffered() -> Self {
+        CrosstermBackend(vec![])
+    }
+    /// Take all the bytes written so far
+    pub fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.0)
+    }
+}
 
 #[cfg(feature="backend-crossterm-event")]
 #[derive(Debug, Clone)]
@@ -103,19 +129,10 @@ impl BackendEventReader for CrosstermEventReader {
     type EventError = io::Error;
 
     fn read_events(&mut self) -> Result<Event, Self::EventError> {
-        use crossterm::event::Event as E;
-
-        Ok(match crossterm::event::read()? {
-            E::Key(key) => {
-                let code = KeyCode::from(key.code);
-                Event::Key(Key(key.modifiers.into(), code), code)
-            },
-            E::Mouse(mouse) => Event::Mouse(mouse.into(), mouse.column, mouse.row),
-            E::Paste(data) => Event::Paste(data),
-            E::Resize(w, h) => Event::Resize(w, h),
-            E::FocusGained => Event::Focus,
-            E::FocusLost => Event::Blur,
-        })
+        Ok(crossterm::event::read()?.into())
+    }
+    fn poll(&mut self, timeout: Duration) -> Result<bool, Self::EventError> {
+        crossterm::event::poll(timeout)
     }
 }
 
@@ -128,8 +145,100 @@ impl<W: Write> BackendEvent for CrosstermBackend<W> {
     }
 }
 
+/// Async [crossterm](https://docs.rs/crossterm) events stream
+/// Yields the same events as [CrosstermEventReader], but without blocking
+///
+/// # Notes
+///
+/// - items are `io::Result<Event>` rather than plain [Event], since reading the terminal can fail
+///   the same way [CrosstermEventReader] can, the stream is not ended on errors
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> std::io::Result<()> {
+/// use tuich::backend::{crossterm::CrosstermBackend, BackendEventStream};
+///
+/// let backend = CrosstermBackend::default();
+/// let mut stream = backend.event_stream();
+/// // Use it in `select!` or with `StreamExt::next()` of your favorite async runtime
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature="async")]
+#[derive(Debug, Default)]
+pub struct EventStream(crossterm::event::EventStream);
+#[cfg(feature="async")]
+impl EventStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+#[cfg(feature="async")]
+impl futures_core::Stream for EventStream {
+    type Item = io::Result<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|e| e.map(|e| e.map(Event::from)))
+    }
+}
+
+#[cfg(feature="async")]
+impl<W: Write> BackendEventStream for CrosstermBackend<W> {
+    type EventStream = EventStream;
+
+    fn event_stream(&self) -> Self::EventStream {
+        EventStream::new()
+    }
+}
+
+impl<W: Write> Terminal<CrosstermBackend<W>> {
+    /// Draw the buffer into memory and return the written bytes instead of writing them to the backend
+    /// Write them into an async writer (for example `tokio::io::stdout()`) to display the frame without blocking
+    ///
+    /// # Notes
+    ///
+    /// - if [Terminal::auto_resize] is enabled and the size has changed,
+    ///   the returned bytes start with clearing the screen
+    /// - the backend itself is left untouched, so the terminal state is still reset on drop
+    pub fn draw_to_vec(&mut self) -> io::Result<Vec<u8>> {
+        let mut frame = CrosstermBackend::buffered();
+
+        if self.auto_resize && self.update_size()? {
+            frame.clear()?;
+        }
+        frame.write_buffer(&self.buffer)?;
+
+        Ok(frame.take())
+    }
+}
+
 // Utils
 #[cfg(feature="backend-crossterm-event")]
+impl From<crossterm::event::Event> for Event {
+    fn from(value: crossterm::event::Event) -> Self {
+        use crossterm::event::Event as E;
+
+        match value {
+            E::Key(key) => {
+                let code = KeyCode::from(key.code);
+                Event::Key(
+                    Key(key.modifiers.into(), code),
+                    code,
+                    KeyInfo::new(key.kind.into(), key.state.into())
+                )
+            },
+            E::Mouse(mouse) => Event::Mouse(mouse.into(), mouse.column, mouse.row),
+            E::Paste(data) => Event::Paste(data),
+            E::Resize(w, h) => Event::Resize(w, h),
+            E::FocusGained => Event::Focus,
+            E::FocusLost => Event::Blur,
+        }
+    }
+}
+#[cfg(feature="backend-crossterm-event")]
 impl From<crossterm::event::KeyCode> for KeyCode {
     fn from(value: crossterm::event::KeyCode) -> Self {
         use crossterm::event::KeyCode as C;
@@ -172,6 +281,31 @@ impl From<crossterm::event::KeyCode> for KeyCode {
     }
 }
 
+#[cfg(feature="backend-crossterm-event")]
+impl From<crossterm::event::KeyEventKind> for KeyKind {
+    fn from(value: crossterm::event::KeyEventKind) -> Self {
+        use crossterm::event::KeyEventKind as K;
+
+        match value {
+            K::Press => Self::Press,
+            K::Repeat => Self::Repeat,
+            K::Release => Self::Release,
+        }
+    }
+}
+#[cfg(feature="backend-crossterm-event")]
+impl From<crossterm::event::KeyEventState> for KeyState {
+    fn from(value: crossterm::event::KeyEventState) -> Self {
+        use crossterm::event::KeyEventState as S;
+
+        Self {
+            keypad: value.contains(S::KEYPAD),
+            caps_lock: value.contains(S::CAPS_LOCK),
+            num_lock: value.contains(S::NUM_LOCK),
+        }
+    }
+}
+
 #[cfg(feature="backend-crossterm-event")]
 impl From<crossterm::event::ModifierKeyCode> for ModKeyCode {
     fn from(value: crossterm::event::ModifierKeyCode) -> Self {
@@ -181,9 +315,9 @@ impl From<crossterm::event::ModifierKeyCode> for ModKeyCode {
             C::LeftShift | C::RightShift => Self::Shift,
             C::LeftControl | C::RightControl => Self::Ctrl,
             C::LeftAlt | C::RightAlt => Self::Alt,
-            C::LeftMeta | C::RightMeta |
-            C::LeftHyper | C::RightHyper |
             C::LeftSuper | C::RightSuper => Self::Super,
+            C::LeftHyper | C::RightHyper => Self::Hyper,
+            C::LeftMeta | C::RightMeta => Self::Meta,
             _ => Self::Unknown
         }
     }
@@ -226,14 +360,14 @@ impl From<crossterm::event::KeyModifiers> for KeyMod {
     fn from(value: crossterm::event::KeyModifiers) -> Self {
         use crossterm::event::KeyModifiers as M;
 
-        if value == M::SHIFT { Self::Shift }
-        else if value == M::CONTROL { Self::Ctrl }
-        else if value == M::ALT { Self::Alt }
-        else if value == M::SHIFT | M::CONTROL { Self::ShiftCtrl }
-        else if value == M::SHIFT | M::ALT { Self::ShiftAlt }
-        else if value == M::SHIFT | M::CONTROL | M::ALT { Self::ShiftCtrlAlt }
-        else if value == M::CONTROL | M::ALT { Self::CtrlAlt }
-        else { Self::None }
+        let mut mods = Self::NONE;
+        mods.set(Self::SHIFT, value.contains(M::SHIFT));
+        mods.set(Self::CTRL, value.contains(M::CONTROL));
+        mods.set(Self::ALT, value.contains(M::ALT));
+        mods.set(Self::SUPER, value.contains(M::SUPER));
+        mods.set(Self::HYPER, value.contains(M::HYPER));
+        mods.set(Self::META, value.contains(M::META));
+        mods
     }
 }
 
@@ -309,3 +443,52 @@ pub fn write_style(f: &mut impl Write, style: &Style, last_style: &Style) -> io:
         SetForegroundColor(color_to_crossterm(style.fg.unwrap_or(Color::Reset)))
     )
 }
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use crate::buffer::Buffer;
+
+    use super::*;
+
+    #[cfg(feature="backend-crossterm-event")]
+    #[test]
+    fn from_event() {
+        use crossterm::event::{self as ct, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+
+        let key = KeyEvent::new(ct::KeyCode::Char('a'), KeyModifiers::CONTROL);
+        assert_eq!(
+            Event::from(ct::Event::Key(key)),
+            Event::Key(Key(KeyMod::CTRL, KeyCode::Char('a')), KeyCode::Char('a'), KeyInfo::default())
+        );
+
+        let mouse = MouseEvent {
+            kind: MouseEventKind::Down(ct::MouseButton::Left),
+            column: 3,
+            row: 4,
+            modifiers: KeyModifiers::NONE,
+        };
+        assert_eq!(Event::from(ct::Event::Mouse(mouse)), Event::Mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 4), 3, 4));
+
+        assert_eq!(Event::from(ct::Event::Paste("hi".into())), Event::Paste("hi".into()));
+        assert_eq!(Event::from(ct::Event::Resize(1, 2)), Event::Resize(1, 2));
+        assert_eq!(Event::from(ct::Event::FocusGained), Event::Focus);
+        assert_eq!(Event::from(ct::Event::FocusLost), Event::Blur);
+    }
+
+    #[test]
+    fn draw_to_vec() {
+        let mut buffer = Buffer::empty(2, 1);
+        buffer.set_char((0, 0), Some("a"));
+        let mut term = Terminal::new(CrosstermBackend::buffered(), buffer);
+
+        let frame = term.draw_to_vec().unwrap();
+
+        let mut expected = CrosstermBackend::buffered();
+        expected.write_buffer(&term.buffer).unwrap();
+        assert_eq!(frame, expected.take());
+        assert!(String::from_utf8(frame).unwrap().contains("a "));
+        assert!(term.backend.0.is_empty(), "Backend is not written to");
+    }
+}
@@ -2,5 +2,10 @@ mod backend;
 
 pub use backend::*;
 
+#[cfg(feature="backend-event")]
+mod script;
+#[cfg(feature="backend-event")]
+pub use script::*;
+
 #[cfg(feature="backend-crossterm")]
 pub mod crossterm;
@@ -0,0 +1,459 @@
+use std::{collections::VecDeque, fmt::{self, Display}, thread, time::{Duration, Instant}};
+
+#[cfg(feature="serde")]
+use std::{fs, io, path::Path};
+
+use crate::event::Event;
+
+use super::BackendEventReader;
+
+/// Scripted event
+/// An event with a delay before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub struct ScriptedEvent {
+    pub event: Event,
+    /// Delay in milliseconds since the previous event
+    #[cfg_attr(feature="serde", serde(default, skip_serializing_if="is_zero"))]
+    pub delay: u64,
+}
+impl ScriptedEvent {
+    pub fn new(event: Event) -> Self {
+        Self { event, delay: 0 }
+    }
+
+    //
+
+    /// Set delay since the previous event
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay.as_millis().try_into().unwrap_or(u64::MAX);
+        self
+    }
+
+    /// Delay since the previous event
+    pub fn delay_duration(&self) -> Duration {
+        Duration::from_millis(self.delay)
+    }
+}
+impl From<Event> for ScriptedEvent {
+    fn from(value: Event) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(feature="serde")]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Event script
+/// A list of events that can be loaded from or saved to a file with any serde format
+///
+/// # Examples
+///
+/// ```toml
+/// [[events]]
+/// event = { key = ["a", "a", { kind = "press" }] }
+///
+/// [[events]]
+/// event = "focus"
+/// delay = 500
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub struct EventScript {
+    #[cfg_attr(feature="serde", serde(default))]
+    pub events: Vec<ScriptedEvent>,
+}
+impl EventScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //
+
+    /// Add an event without delay
+    pub fn event<E: Into<ScriptedEvent>>(mut self, event: E) -> Self {
+        self.events.push(event.into());
+        self
+    }
+    /// Add an event with a delay before it
+    pub fn delayed_event(mut self, delay: Duration, event: Event) -> Self {
+        self.events.push(ScriptedEvent::new(event).delay(delay));
+        self
+    }
+
+    // File
+
+    /// Load a script from a file
+    /// `from_str` parses the file content with any serde format, for example `toml::from_str`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tuich::backend::*;
+    /// let script = EventScript::load("bug.toml", toml::from_str)?;
+    /// let reader = ScriptedEventReader::new(script);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature="serde")]
+    pub fn load<P, F, E>(path: P, from_str: F) -> io::Result<Self>
+    where P: AsRef<Path>,
+          F: FnOnce(&str) -> Result<Self, E>,
+          E: std::error::Error + Send + Sync + 'static
+    {
+        let content = fs::read_to_string(path)?;
+        from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Save the script to a file, overwriting it
+    /// `to_string` serializes the script with any serde format, for example `toml::to_string`
+    #[cfg(feature="serde")]
+    pub fn save<P, F, E>(&self, path: P, to_string: F) -> io::Result<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&Self) -> Result<String, E>,
+          E: std::error::Error + Send + Sync + 'static
+    {
+        let content = to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+}
+impl<E: Into<ScriptedEvent>> FromIterator<E> for EventScript {
+    fn from_iter<T: IntoIterator<Item = E>>(iter: T) -> Self {
+        Self { events: iter.into_iter().map(Into::into).collect() }
+    }
+}
+impl From<Vec<Event>> for EventScript {
+    fn from(value: Vec<Event>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+/// Error of [ScriptedEventReader]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptEnd;
+impl Display for ScriptEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no more scripted events")
+    }
+}
+impl std::error::Error for ScriptEnd {}
+
+/// Scripted event reader
+/// Replays a list of events, for example in tests or to reproduce a bug headlessly
+///
+/// Delays are ignored unless [ScriptedEventReader::realtime] is enabled.
+/// When there are no more events, reading returns [ScriptEnd]
+/// Scripts can be loaded from a file with [EventScript::load]
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::{backend::*, event::*};
+/// let mut reader = ScriptedEventReader::new(vec![
+///     Event::Focus,
+///     Event::Paste("hello".into()),
+/// ]);
+///
+/// assert_eq!(reader.read_events(), Ok(Event::Focus));
+/// assert_eq!(reader.try_read(), Ok(Some(Event::Paste("hello".into()))));
+/// assert_eq!(reader.read_events(), Err(ScriptEnd));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScriptedEventReader {
+    /// Whether to wait for event delays
+    pub realtime: bool,
+
+    events: VecDeque<ScriptedEvent>,
+    last_time: Option<Instant>,
+}
+impl ScriptedEventReader {
+    pub fn new<S: Into<EventScript>>(script: S) -> Self {
+        Self {
+            realtime: false,
+            events: script.into().events.into(),
+            last_time: None,
+        }
+    }
+
+    //
+
+    /// Set whether to wait for event delays
+    pub fn realtime(mut self, value: bool) -> Self {
+        self.realtime = value;
+        self
+    }
+
+    /// Add an event to the end of the script
+    pub fn push<E: Into<ScriptedEvent>>(&mut self, event: E) {
+        self.events.push_back(event.into());
+    }
+    /// Events that are not read yet
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+    /// Returns `true` if all events have been read
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Time left until the next event is available
+    fn time_left(&mut self) -> Duration {
+        if !self.realtime {
+            return Duration::ZERO;
+        }
+
+        let Some(next) = self.events.front() else {
+            return Duration::ZERO;
+        };
+        let now = Instant::now();
+        let last_time = *self.last_time.get_or_insert(now);
+
+        next.delay_duration().saturating_sub(now.saturating_duration_since(last_time))
+    }
+}
+impl BackendEventReader for ScriptedEventReader {
+    type EventError = ScriptEnd;
+
+    fn read_events(&mut self) -> Result<Event, Self::EventError> {
+        thread::sleep(self.time_left());
+
+        let next = self.events.pop_front().ok_or(ScriptEnd)?;
+        self.last_time = Some(Instant::now());
+        Ok(next.event)
+    }
+    /// Returns `true` when the script is finished, so the next read returns [ScriptEnd]
+    fn poll(&mut self, timeout: Duration) -> Result<bool, Self::EventError> {
+        let left = self.time_left();
+
+        if left <= timeout {
+            thread::sleep(left);
+            Ok(true)
+        } else {
+            thread::sleep(timeout);
+            Ok(false)
+        }
+    }
+}
+impl From<EventScript> for ScriptedEventReader {
+    fn from(value: EventScript) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Event recorder callback
+pub type RecordCallback = Box<dyn FnMut(&ScriptedEvent)>;
+
+/// Event recorder
+/// Wraps any event reader and records read events with delays between them
+///
+/// The recorded [EventScript] can be saved to a file with any serde format (see [EventRecorder::save])
+/// and replayed with [ScriptedEventReader].
+/// Use [EventRecorder::on_record] to save events as they come, so nothing is lost on a crash
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::{backend::*, event::*};
+/// let reader = ScriptedEventReader::new(vec![Event::Focus, Event::Blur]);
+/// let mut recorder = EventRecorder::new(reader);
+///
+/// while recorder.read_events().is_ok() {}
+///
+/// let events: Vec<_> = recorder.script().events.iter().map(|e| e.event.clone()).collect();
+/// assert_eq!(events, vec![Event::Focus, Event::Blur]);
+/// ```
+pub struct EventRecorder<R: BackendEventReader> {
+    reader: R,
+    script: EventScript,
+    last_time: Instant,
+    callbacks: Vec<RecordCallback>,
+}
+impl<R: BackendEventReader> EventRecorder<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            script: EventScript::new(),
+            last_time: Instant::now(),
+            callbacks: vec![],
+        }
+    }
+
+    //
+
+    /// Add a callback that is called on each recorded event
+    pub fn on_record<F: FnMut(&ScriptedEvent) + 'static>(mut self, f: F) -> Self {
+        self.callbacks.push(Box::new(f));
+        self
+    }
+
+    fn record(&mut self, event: &Event) {
+        let now = Instant::now();
+        let scripted = ScriptedEvent::new(event.clone())
+            .delay(now.saturating_duration_since(self.last_time));
+        self.last_time = now;
+
+        for callback in &mut self.callbacks {
+            callback(&scripted);
+        }
+
+        self.script.events.push(scripted);
+    }
+
+    /// Recorded events
+    pub fn script(&self) -> &EventScript {
+        &self.script
+    }
+    /// Take recorded events, leaving the recorder empty
+    pub fn take_script(&mut self) -> EventScript {
+        std::mem::take(&mut self.script)
+    }
+    /// Wrapped reader
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+    /// Returns the wrapped reader and recorded events
+    pub fn into_inner(self) -> (R, EventScript) {
+        (self.reader, self.script)
+    }
+
+    /// Save recorded events to a file, overwriting it
+    /// See [EventScript::save]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tuich::{backend::*, event::*};
+    /// # let reader = ScriptedEventReader::new(vec![Event::Focus]);
+    /// let mut recorder = EventRecorder::new(reader);
+    ///
+    /// while let Ok(event) = recorder.read_events() {
+    ///     // Handle the event
+    ///     recorder.save("events.toml", toml::to_string)?;
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature="serde")]
+    pub fn save<P, F, E>(&self, path: P, to_string: F) -> io::Result<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&EventScript) -> Result<String, E>,
+          E: std::error::Error + Send + Sync + 'static
+    {
+        self.script.save(path, to_string)
+    }
+}
+impl<R: BackendEventReader> BackendEventReader for EventRecorder<R> {
+    type EventError = R::EventError;
+
+    fn read_events(&mut self) -> Result<Event, Self::EventError> {
+        let event = self.reader.read_events()?;
+        self.record(&event);
+        Ok(event)
+    }
+    fn poll(&mut self, timeout: Duration) -> Result<bool, Self::EventError> {
+        self.reader.poll(timeout)
+    }
+}
+impl<R: BackendEventReader + fmt::Debug> fmt::Debug for EventRecorder<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventRecorder")
+            .field("reader", &self.reader)
+            .field("script", &self.script)
+            .finish_non_exhaustive()
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn realtime() {
+        // The delay is long enough to never pass during the test
+        let mut reader = ScriptedEventReader::new(
+            EventScript::new()
+                .event(Event::Focus)
+                .delayed_event(Duration::from_secs(60), Event::Blur)
+        ).realtime(true);
+
+        assert_eq!(reader.try_read(), Ok(Some(Event::Focus)));
+        assert_eq!(reader.try_read(), Ok(None), "Delay has not passed yet");
+        assert_eq!(reader.remaining(), 1);
+
+        // Short delays are waited for, events keep their order
+        let mut reader = ScriptedEventReader::new(
+            EventScript::new()
+                .delayed_event(Duration::from_millis(2), Event::Focus)
+                .delayed_event(Duration::from_millis(1), Event::Resize(1, 2))
+                .event(Event::Blur)
+        ).realtime(true);
+
+        assert_eq!(reader.read_events(), Ok(Event::Focus));
+        assert_eq!(reader.read_timeout(Duration::from_secs(60)), Ok(Some(Event::Resize(1, 2))));
+        assert_eq!(reader.read_events(), Ok(Event::Blur));
+        assert!(reader.is_finished());
+        assert_eq!(reader.try_read(), Err(ScriptEnd));
+    }
+
+    #[test]
+    fn record() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let logged = Rc::new(RefCell::new(vec![]));
+        let logged_clone = Rc::clone(&logged);
+
+        let mut recorder = EventRecorder::new(ScriptedEventReader::new(vec![Event::Focus, Event::Resize(1, 2)]))
+            .on_record(move |e| logged_clone.borrow_mut().push(e.event.clone()));
+
+        assert_eq!(recorder.read_events(), Ok(Event::Focus));
+        assert_eq!(recorder.read_events(), Ok(Event::Resize(1, 2)));
+        assert_eq!(recorder.read_events(), Err(ScriptEnd));
+
+        assert_eq!(*logged.borrow(), vec![Event::Focus, Event::Resize(1, 2)]);
+
+        let mut replay = ScriptedEventReader::new(recorder.take_script());
+        assert_eq!(replay.read_events(), Ok(Event::Focus));
+        assert_eq!(replay.read_events(), Ok(Event::Resize(1, 2)));
+        assert!(recorder.script().events.is_empty());
+    }
+}
+
+#[cfg(feature="serde")]
+#[cfg(test)]
+mod serde_tests {
+    use crate::event::{Key, KeyCode, KeyInfo, KeyMod};
+
+    use super::*;
+
+    #[test]
+    fn script_round_trip() {
+        let script = EventScript::new()
+            .event(Event::Key(Key(KeyMod::CTRL, KeyCode::Char('a')), KeyCode::Char('a'), KeyInfo::default()))
+            .delayed_event(Duration::from_millis(250), Event::Paste("hi".into()));
+
+        let s = toml::to_string(&script).unwrap();
+        let loaded: EventScript = toml::from_str(&s).unwrap();
+
+        assert_eq!(loaded, script);
+        assert_eq!(loaded.events[1].delay_duration(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("tuich_script_{}.toml", std::process::id()));
+
+        let mut recorder = EventRecorder::new(ScriptedEventReader::new(vec![Event::Focus, Event::Paste("hi".into())]));
+        while recorder.read_events().is_ok() {}
+        recorder.save(&path, toml::to_string).unwrap();
+
+        let loaded = EventScript::load(&path, toml::from_str).unwrap();
+        std::fs::write(&path, "events = 1").unwrap();
+        let invalid = EventScript::load(&path, toml::from_str);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&loaded, recorder.script());
+        assert_eq!(invalid.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert!(EventScript::load(&path, toml::from_str).is_err(), "Missing file");
+    }
+}
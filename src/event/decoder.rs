@@ -0,0 +1,647 @@
+use super::{Event, Key, KeyCode, KeyInfo, KeyKind, KeyMod, KeyState, ModKeyCode, Mouse, MouseBtn};
+
+const ESC: u8 = 0x1b;
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+enum Parsed {
+    /// More bytes are needed
+    Incomplete,
+    /// An event and the number of bytes it took
+    Event(Event, usize),
+}
+
+/// Raw terminal input decoder
+/// Turns raw bytes read from a terminal in raw mode into [Event]s
+///
+/// Supports:
+/// - plain, control and UTF-8 characters, `Esc` prefix as `Alt`
+/// - CSI and SS3 keys with modifiers
+/// - xterm `modifyOtherKeys` (`CSI 27 ; mods ; code ~`)
+/// - kitty keyboard protocol (`CSI code ; mods:kind u`), including key releases and modifier keys
+/// - SGR (`CSI < ...`) and X10 (`CSI M ...`) mouse
+/// - bracketed paste and focus events
+///
+/// # Lone Esc
+///
+/// A single `Esc` byte may be either the `Esc` key or the start of a sequence that has not fully arrived yet.
+/// When [InputDecoder::is_pending] returns `true`, wait a short time (for example 50ms) for more bytes
+/// and call [InputDecoder::flush] if none arrive
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::event::*;
+/// let mut decoder = InputDecoder::new();
+///
+/// let events = decoder.decode(b"a\x1b[1;5A\x1bx");
+/// assert_eq!(events, vec![
+///     Event::Key(Key(KeyMod::NONE, KeyCode::Char('a')), KeyCode::Char('a'), KeyInfo::default()),
+///     Event::Key(Key(KeyMod::CTRL, KeyCode::Up), KeyCode::Up, KeyInfo::default()),
+///     Event::Key(Key(KeyMod::ALT, KeyCode::Char('x')), KeyCode::Char('x'), KeyInfo::default()),
+/// ]);
+///
+/// // Esc key or the start of a sequence?
+/// assert_eq!(decoder.decode(b"\x1b"), vec![]);
+/// assert!(decoder.is_pending());
+/// // No more bytes came in time
+/// assert_eq!(decoder.flush(), vec![
+///     Event::Key(Key(KeyMod::NONE, KeyCode::Esc), KeyCode::Esc, KeyInfo::default()),
+/// ]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InputDecoder {
+    buf: Vec<u8>,
+}
+impl InputDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //
+
+    /// Add bytes to decode
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+    /// Decode the next complete event
+    /// Returns `None` if more bytes are needed
+    pub fn next_event(&mut self) -> Option<Event> {
+        match parse(&self.buf) {
+            Parsed::Incomplete => None,
+            Parsed::Event(event, len) => {
+                self.buf.drain(..len);
+                Some(event)
+            }
+        }
+    }
+    /// Add bytes and decode all complete events
+    pub fn decode(&mut self, bytes: &[u8]) -> Vec<Event> {
+        self.feed(bytes);
+        std::iter::from_fn(|| self.next_event()).collect()
+    }
+    /// Decode all remaining bytes, treating incomplete sequences as complete
+    /// A lone `Esc` becomes the `Esc` key, `Esc` with one more byte becomes an `Alt` key
+    ///
+    /// Call it when no more bytes arrived for some time (see [InputDecoder#lone-esc])
+    ///
+    /// # Notes
+    ///
+    /// - an unfinished bracketed paste is kept, since the rest of a long paste may still be coming
+    pub fn flush(&mut self) -> Vec<Event> {
+        let mut events: Vec<Event> = std::iter::from_fn(|| self.next_event()).collect();
+
+        while !self.buf.is_empty() && !self.buf.starts_with(PASTE_START) {
+            let (event, len) = match self.buf.as_slice() {
+                [ESC] => (key_event(KeyMod::NONE, KeyCode::Esc, KeyInfo::default()), 1),
+                [ESC, b] if *b != ESC => match parse_key(&self.buf[1..], KeyMod::ALT) {
+                    Parsed::Event(event, len) => (event, len + 1),
+                    Parsed::Incomplete => (Event::Unknown, self.buf.len()),
+                },
+                buf => (Event::Unknown, buf.len()),
+            };
+
+            self.buf.drain(..len);
+            events.push(event);
+            events.extend(std::iter::from_fn(|| self.next_event()));
+        }
+
+        events
+    }
+
+    /// Returns `true` if there are bytes of an incomplete event
+    pub fn is_pending(&self) -> bool {
+        !self.buf.is_empty()
+    }
+    /// Forget all pending bytes
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+fn key_event(mods: KeyMod, code: KeyCode, info: KeyInfo) -> Event {
+    Event::Key(Key(mods, code), code, info)
+}
+fn char_event(mods: KeyMod, char: char, info: KeyInfo) -> Event {
+    let mods = if char.is_uppercase() { mods | KeyMod::SHIFT } else { mods };
+    key_event(mods, KeyCode::Char(char), info)
+}
+
+fn parse(buf: &[u8]) -> Parsed {
+    let Some(&first) = buf.first() else {
+        return Parsed::Incomplete;
+    };
+    if first != ESC {
+        return parse_key(buf, KeyMod::NONE);
+    }
+
+    match buf.get(1) {
+        None => Parsed::Incomplete,
+        Some(b'[') => parse_csi(buf),
+        Some(b'O') => parse_ss3(buf),
+        Some(&ESC) => Parsed::Event(key_event(KeyMod::NONE, KeyCode::Esc, KeyInfo::default()), 1),
+        // Esc prefix is Alt
+        Some(_) => match parse_key(&buf[1..], KeyMod::ALT) {
+            Parsed::Event(event, len) => Parsed::Event(event, len + 1),
+            Parsed::Incomplete => Parsed::Incomplete,
+        },
+    }
+}
+
+/// Parse a single key that doesn't start with Esc
+fn parse_key(buf: &[u8], mods: KeyMod) -> Parsed {
+    let info = KeyInfo::default();
+    let b = buf[0];
+
+    let (code, len) = match b {
+        b'\r' => (KeyCode::Enter, 1),
+        b'\t' => (KeyCode::Tab, 1),
+        0x7f => (KeyCode::Backspace, 1),
+        0x00 => return Parsed::Event(key_event(mods | KeyMod::CTRL, KeyCode::Char(' '), info), 1),
+        0x01..=0x1a => {
+            let char = (b - 0x01 + b'a') as char;
+            return Parsed::Event(key_event(mods | KeyMod::CTRL, KeyCode::Char(char), info), 1);
+        },
+        0x1c..=0x1f => {
+            let char = (b - 0x1c + b'4') as char;
+            return Parsed::Event(key_event(mods | KeyMod::CTRL, KeyCode::Char(char), info), 1);
+        },
+        _ => {
+            let len = match b {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Parsed::Event(Event::Unknown, 1),
+            };
+            if buf.len() < len {
+                return Parsed::Incomplete;
+            }
+
+            return match std::str::from_utf8(&buf[..len]).ok().and_then(|s| s.chars().next()) {
+                Some(char) => Parsed::Event(char_event(mods, char, info), len),
+                None => Parsed::Event(Event::Unknown, 1),
+            };
+        }
+    };
+
+    Parsed::Event(key_event(mods, code, info), len)
+}
+
+/// Parse `Esc O <char>`
+fn parse_ss3(buf: &[u8]) -> Parsed {
+    let Some(&b) = buf.get(2) else {
+        return Parsed::Incomplete;
+    };
+
+    let code = match b {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'M' => KeyCode::Enter,
+        b'P'..=b'S' => KeyCode::F(b - b'P' + 1),
+        _ => return Parsed::Event(Event::Unknown, 3),
+    };
+
+    Parsed::Event(key_event(KeyMod::NONE, code, KeyInfo::default()), 3)
+}
+
+/// CSI parameters, each one can have `:` separated sub-parameters
+struct Params(Vec<Vec<Option<u32>>>);
+impl Params {
+    fn parse(s: &str) -> Self {
+        Self(
+            s.split(';')
+                .map(|p| p.split(':').map(|n| n.parse().ok()).collect())
+                .collect()
+        )
+    }
+
+    fn get(&self, index: usize, sub: usize) -> Option<u32> {
+        self.0.get(index)?.get(sub).copied().flatten()
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Modifiers and event kind in the xterm/kitty form `1 + mods[:kind]`
+    fn mods(&self, index: usize) -> (KeyMod, KeyInfo) {
+        let bits = self.get(index, 0).unwrap_or(1).saturating_sub(1);
+
+        let mut mods = KeyMod::NONE;
+        mods.set(KeyMod::SHIFT, bits & 1 != 0);
+        mods.set(KeyMod::ALT, bits & 2 != 0);
+        mods.set(KeyMod::CTRL, bits & 4 != 0);
+        mods.set(KeyMod::SUPER, bits & 8 != 0);
+        mods.set(KeyMod::HYPER, bits & 16 != 0);
+        mods.set(KeyMod::META, bits & 32 != 0);
+
+        let state = KeyState {
+            keypad: false,
+            caps_lock: bits & 64 != 0,
+            num_lock: bits & 128 != 0,
+        };
+        let kind = match self.get(index, 1) {
+            Some(2) => KeyKind::Repeat,
+            Some(3) => KeyKind::Release,
+            _ => KeyKind::Press,
+        };
+
+        (mods, KeyInfo::new(kind, state))
+    }
+}
+
+/// Parse `Esc [ <params> <final>`
+fn parse_csi(buf: &[u8]) -> Parsed {
+    let mut end = 2;
+    loop {
+        match buf.get(end) {
+            None => return Parsed::Incomplete,
+            Some(0x20..=0x3f) => end += 1,
+            Some(0x40..=0x7e) => break,
+            // Broken sequence
+            Some(_) => return Parsed::Event(Event::Unknown, end),
+        }
+    }
+
+    let final_byte = buf[end];
+    let len = end + 1;
+    // Only ASCII bytes are allowed before the final byte
+    let params = std::str::from_utf8(&buf[2..end]).unwrap_or_default();
+
+    // X10 mouse, `CSI M` followed by 3 raw bytes
+    if final_byte == b'M' && params.is_empty() {
+        let Some(&[cb, cx, cy]) = buf.get(len..len + 3) else {
+            return Parsed::Incomplete;
+        };
+        let x = (cx as u16).saturating_sub(33);
+        let y = (cy as u16).saturating_sub(33);
+        return Parsed::Event(mouse_event(cb.saturating_sub(32) as u32, x, y, false), len + 3);
+    }
+
+    // Bracketed paste
+    if final_byte == b'~' && params == "200" {
+        let content = &buf[len..];
+        let Some(pos) = content.windows(PASTE_END.len()).position(|w| w == PASTE_END) else {
+            return Parsed::Incomplete;
+        };
+        let text = String::from_utf8_lossy(&content[..pos]).into_owned();
+        return Parsed::Event(Event::Paste(text), len + pos + PASTE_END.len());
+    }
+
+    // SGR mouse
+    if let Some(params) = params.strip_prefix('<') {
+        if !matches!(final_byte, b'M' | b'm') {
+            return Parsed::Event(Event::Unknown, len);
+        }
+
+        let params = Params::parse(params);
+        let (Some(cb), Some(x), Some(y)) = (params.get(0, 0), params.get(1, 0), params.get(2, 0)) else {
+            return Parsed::Event(Event::Unknown, len);
+        };
+        let x = (x as u16).saturating_sub(1);
+        let y = (y as u16).saturating_sub(1);
+        return Parsed::Event(mouse_event(cb, x, y, final_byte == b'm'), len);
+    }
+
+    // Private sequences, such as terminal replies
+    if params.starts_with(|c: char| !c.is_ascii_digit() && c != ';' && c != ':') {
+        return Parsed::Event(Event::Unknown, len);
+    }
+
+    let params = Params::parse(params);
+    let (mods, info) = params.mods(1);
+
+    let event = match final_byte {
+        b'I' if params.get(0, 0).is_none() => Event::Focus,
+        b'O' if params.get(0, 0).is_none() => Event::Blur,
+
+        b'A' => key_event(mods, KeyCode::Up, info),
+        b'B' => key_event(mods, KeyCode::Down, info),
+        b'C' => key_event(mods, KeyCode::Right, info),
+        b'D' => key_event(mods, KeyCode::Left, info),
+        b'E' => key_event(mods, KeyCode::KeypadBegin, info),
+        b'H' => key_event(mods, KeyCode::Home, info),
+        b'F' => key_event(mods, KeyCode::End, info),
+        b'P'..=b'S' => key_event(mods, KeyCode::F(final_byte - b'P' + 1), info),
+        b'Z' => key_event(mods | KeyMod::SHIFT, KeyCode::BackTab, info),
+
+        // xterm modifyOtherKeys
+        b'~' if params.get(0, 0) == Some(27) && params.len() >= 3 => {
+            let (mods, info) = params.mods(1);
+            match params.get(2, 0) {
+                Some(code) => code_event(code, None, mods, info),
+                None => Event::Unknown,
+            }
+        },
+        b'~' => match tilde_code(params.get(0, 0).unwrap_or(0)) {
+            Some(code) => key_event(mods, code, info),
+            None => Event::Unknown,
+        },
+
+        // kitty keyboard protocol
+        b'u' => match params.get(0, 0) {
+            Some(code) => code_event(code, params.get(0, 1), mods, info),
+            None => Event::Unknown,
+        },
+
+        _ => Event::Unknown,
+    };
+
+    Parsed::Event(event, len)
+}
+
+/// Key code of `CSI <n> ~`
+fn tilde_code(n: u32) -> Option<KeyCode> {
+    Some(match n {
+        1 | 7 => KeyCode::Home,
+        2 => KeyCode::Insert,
+        3 => KeyCode::Delete,
+        4 | 8 => KeyCode::End,
+        5 => KeyCode::PageUp,
+        6 => KeyCode::PageDown,
+        11..=15 => KeyCode::F((n - 10) as u8),
+        17..=21 => KeyCode::F((n - 11) as u8),
+        23..=26 => KeyCode::F((n - 12) as u8),
+        28 | 29 => KeyCode::F((n - 13) as u8),
+        31..=34 => KeyCode::F((n - 14) as u8),
+        _ => return None,
+    })
+}
+
+/// Key event from a unicode code point or a kitty functional key code
+fn code_event(code: u32, shifted: Option<u32>, mods: KeyMod, mut info: KeyInfo) -> Event {
+    info.state.keypad = (57399..=57427).contains(&code);
+
+    let code = match code {
+        27 => KeyCode::Esc,
+        13 => KeyCode::Enter,
+        9 => KeyCode::Tab,
+        8 | 127 => KeyCode::Backspace,
+
+        57358 => KeyCode::CapsLock,
+        57359 => KeyCode::ScrollLock,
+        57360 => KeyCode::NumLock,
+        57361 => KeyCode::PrintScreen,
+        57362 => KeyCode::Pause,
+        57363 => KeyCode::Menu,
+        57376..=57398 => KeyCode::F((code - 57376 + 13) as u8),
+
+        // Keypad
+        57399..=57408 => KeyCode::Char(char::from(b'0' + (code - 57399) as u8)),
+        57409 => KeyCode::Char('.'),
+        57410 => KeyCode::Char('/'),
+        57411 => KeyCode::Char('*'),
+        57412 => KeyCode::Char('-'),
+        57413 => KeyCode::Char('+'),
+        57414 => KeyCode::Enter,
+        57415 => KeyCode::Char('='),
+        57416 => KeyCode::Char(','),
+        57417 => KeyCode::Left,
+        57418 => KeyCode::Right,
+        57419 => KeyCode::Up,
+        57420 => KeyCode::Down,
+        57421 => KeyCode::PageUp,
+        57422 => KeyCode::PageDown,
+        57423 => KeyCode::Home,
+        57424 => KeyCode::End,
+        57425 => KeyCode::Insert,
+        57426 => KeyCode::Delete,
+        57427 => KeyCode::KeypadBegin,
+
+        // Left and right modifier keys
+        57441 | 57447 => KeyCode::Mod(ModKeyCode::Shift),
+        57442 | 57448 => KeyCode::Mod(ModKeyCode::Ctrl),
+        57443 | 57449 => KeyCode::Mod(ModKeyCode::Alt),
+        57444 | 57450 => KeyCode::Mod(ModKeyCode::Super),
+        57445 | 57451 => KeyCode::Mod(ModKeyCode::Hyper),
+        57446 | 57452 => KeyCode::Mod(ModKeyCode::Meta),
+
+        _ => {
+            let code = if mods.shift() { shifted.unwrap_or(code) } else { code };
+            match char::from_u32(code) {
+                Some(char) if mods.shift() && char.is_ascii_lowercase() => {
+                    KeyCode::Char(char.to_ascii_uppercase())
+                },
+                Some(char) => KeyCode::Char(char),
+                None => KeyCode::Unknown,
+            }
+        }
+    };
+
+    key_event(mods, code, info)
+}
+
+fn mouse_event(cb: u32, x: u16, y: u16, release: bool) -> Event {
+    let mut mods = KeyMod::NONE;
+    mods.set(KeyMod::SHIFT, cb & 4 != 0);
+    mods.set(KeyMod::ALT, cb & 8 != 0);
+    mods.set(KeyMod::CTRL, cb & 16 != 0);
+
+    let btn = match cb & 3 {
+        1 => MouseBtn::Middle,
+        2 => MouseBtn::Right,
+        _ => MouseBtn::Left,
+    };
+
+    let mouse = if cb & 64 != 0 {
+        match cb & 3 {
+            0 => Mouse::ScrollUp(mods, x, y),
+            1 => Mouse::ScrollDown(mods, x, y),
+            2 => Mouse::ScrollLeft(mods, x, y),
+            _ => Mouse::ScrollRight(mods, x, y),
+        }
+    } else if cb & 32 != 0 {
+        if cb & 3 == 3 {
+            Mouse::Move(mods, x, y)
+        } else {
+            Mouse::Drag(mods, btn, x, y)
+        }
+    } else if release || cb & 3 == 3 {
+        // X10 doesn't report which button was released
+        Mouse::Up(mods, btn, x, y)
+    } else {
+        Mouse::Down(mods, btn, x, y)
+    };
+
+    Event::Mouse(mouse, x, y)
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(mods: KeyMod, code: KeyCode) -> Event {
+        key_event(mods, code, KeyInfo::default())
+    }
+    fn decode(bytes: &[u8]) -> Vec<Event> {
+        InputDecoder::new().decode(bytes)
+    }
+
+    #[test]
+    fn chars() {
+        assert_eq!(decode(b"aA\r\t\x7f"), vec![
+            key(KeyMod::NONE, KeyCode::Char('a')),
+            key(KeyMod::SHIFT, KeyCode::Char('A')),
+            key(KeyMod::NONE, KeyCode::Enter),
+            key(KeyMod::NONE, KeyCode::Tab),
+            key(KeyMod::NONE, KeyCode::Backspace),
+        ]);
+        assert_eq!(decode(b"\x01\x00\x1f"), vec![
+            key(KeyMod::CTRL, KeyCode::Char('a')),
+            key(KeyMod::CTRL, KeyCode::Char(' ')),
+            key(KeyMod::CTRL, KeyCode::Char('7')),
+        ]);
+        assert_eq!(decode("ё界🦀".as_bytes()), vec![
+            key(KeyMod::NONE, KeyCode::Char('ё')),
+            key(KeyMod::NONE, KeyCode::Char('界')),
+            key(KeyMod::NONE, KeyCode::Char('🦀')),
+        ]);
+
+        let mut decoder = InputDecoder::new();
+        let crab = "🦀".as_bytes();
+        assert_eq!(decoder.decode(&crab[..2]), vec![], "Incomplete UTF-8");
+        assert_eq!(decoder.decode(&crab[2..]), vec![key(KeyMod::NONE, KeyCode::Char('🦀'))]);
+        assert_eq!(decode(b"\xffa"), vec![Event::Unknown, key(KeyMod::NONE, KeyCode::Char('a'))]);
+    }
+
+    #[test]
+    fn esc_and_alt() {
+        assert_eq!(decode(b"\x1ba\x1b\x01\x1b\x1b[A"), vec![
+            key(KeyMod::ALT, KeyCode::Char('a')),
+            key(KeyMod::CTRL_ALT, KeyCode::Char('a')),
+            key(KeyMod::NONE, KeyCode::Esc),
+            key(KeyMod::NONE, KeyCode::Up),
+        ]);
+
+        let mut decoder = InputDecoder::new();
+        assert_eq!(decoder.decode(b"\x1b"), vec![]);
+        assert_eq!(decoder.decode(b"[B"), vec![key(KeyMod::NONE, KeyCode::Down)], "Sequence split between reads");
+
+        assert_eq!(decoder.decode(b"\x1b["), vec![]);
+        assert_eq!(decoder.flush(), vec![key(KeyMod::ALT, KeyCode::Char('['))]);
+        assert_eq!(decoder.decode(b"\x1b[1;"), vec![]);
+        assert_eq!(decoder.flush(), vec![Event::Unknown]);
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn csi_and_ss3() {
+        assert_eq!(decode(b"\x1b[1;5C\x1b[3~\x1b[5;3~\x1b[15~\x1b[24;2~\x1b[Z\x1bOP\x1bOH\x1b[1;6Q"), vec![
+            key(KeyMod::CTRL, KeyCode::Right),
+            key(KeyMod::NONE, KeyCode::Delete),
+            key(KeyMod::ALT, KeyCode::PageUp),
+            key(KeyMod::NONE, KeyCode::F(5)),
+            key(KeyMod::SHIFT, KeyCode::F(12)),
+            key(KeyMod::SHIFT, KeyCode::BackTab),
+            key(KeyMod::NONE, KeyCode::F(1)),
+            key(KeyMod::NONE, KeyCode::Home),
+            key(KeyMod::SHIFT_CTRL, KeyCode::F(2)),
+        ]);
+        assert_eq!(decode(b"\x1b[?1;2c\x1b[99~a"), vec![
+            Event::Unknown,
+            Event::Unknown,
+            key(KeyMod::NONE, KeyCode::Char('a')),
+        ]);
+    }
+
+    #[test]
+    fn modify_other_keys() {
+        assert_eq!(decode(b"\x1b[27;5;13~\x1b[27;6;65~"), vec![
+            key(KeyMod::CTRL, KeyCode::Enter),
+            key(KeyMod::SHIFT_CTRL, KeyCode::Char('A')),
+        ]);
+    }
+
+    #[test]
+    fn kitty() {
+        let release = KeyInfo::from(KeyKind::Release);
+        let keypad = KeyInfo::new(KeyKind::Press, KeyState { keypad: true, ..Default::default() });
+        let caps = KeyInfo::new(KeyKind::Press, KeyState { caps_lock: true, ..Default::default() });
+
+        assert_eq!(decode(b"\x1b[97u\x1b[97;5u\x1b[97;1:3u\x1b[97:65;2u\x1b[27u\x1b[57399u\x1b[57442;5u\x1b[1;1:3A\x1b[97;65u"), vec![
+            key(KeyMod::NONE, KeyCode::Char('a')),
+            key(KeyMod::CTRL, KeyCode::Char('a')),
+            key_event(KeyMod::NONE, KeyCode::Char('a'), release),
+            key(KeyMod::SHIFT, KeyCode::Char('A')),
+            key(KeyMod::NONE, KeyCode::Esc),
+            key_event(KeyMod::NONE, KeyCode::Char('0'), keypad),
+            key(KeyMod::CTRL, KeyCode::Mod(ModKeyCode::Ctrl)),
+            key_event(KeyMod::NONE, KeyCode::Up, release),
+            key_event(KeyMod::NONE, KeyCode::Char('a'), caps),
+        ]);
+    }
+
+    #[test]
+    fn mouse() {
+        let m = KeyMod::NONE;
+
+        assert_eq!(decode(b"\x1b[<0;10;5M\x1b[<0;10;5m\x1b[<34;1;1M\x1b[<35;2;2M\x1b[<65;3;3M\x1b[<16;1;1M"), vec![
+            Event::Mouse(Mouse::Down(m, MouseBtn::Left, 9, 4), 9, 4),
+            Event::Mouse(Mouse::Up(m, MouseBtn::Left, 9, 4), 9, 4),
+            Event::Mouse(Mouse::Drag(m, MouseBtn::Right, 0, 0), 0, 0),
+            Event::Mouse(Mouse::Move(m, 1, 1), 1, 1),
+            Event::Mouse(Mouse::ScrollDown(m, 2, 2), 2, 2),
+            Event::Mouse(Mouse::Down(KeyMod::CTRL, MouseBtn::Left, 0, 0), 0, 0),
+        ]);
+
+        // X10
+        assert_eq!(decode(b"\x1b[M\x20\x21\x22\x1b[M\x23\x21\x22"), vec![
+            Event::Mouse(Mouse::Down(m, MouseBtn::Left, 0, 1), 0, 1),
+            Event::Mouse(Mouse::Up(m, MouseBtn::Left, 0, 1), 0, 1),
+        ]);
+    }
+
+    #[test]
+    fn paste_and_focus() {
+        let mut decoder = InputDecoder::new();
+
+        assert_eq!(decoder.decode(b"\x1b[I\x1b[200~hello\x1b"), vec![Event::Focus]);
+        assert_eq!(decoder.flush(), vec![], "Unfinished paste is kept");
+        assert_eq!(decoder.decode(b"[A\nworld\x1b[201~\x1b[O"), vec![
+            Event::Paste("hello\x1b[A\nworld".into()),
+            Event::Blur,
+        ]);
+    }
+
+    /// Decoding must not panic on any input and must not depend on how the input is split
+    #[test]
+    fn fuzz() {
+        const ALPHABET: &[u8] = b"\x1b\x1b\x1b[[O<;;:0123456789MmuA~Z \x7f\x00\r\xc3\xa9\xf0\x9f\xff";
+
+        let mut seed = 0x2545_f491_u32;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize
+        };
+
+        for _ in 0..500 {
+            let len = random() % 64;
+            let bytes: Vec<u8> = (0..len).map(|_| ALPHABET[random() % ALPHABET.len()]).collect();
+
+            let mut whole = InputDecoder::new();
+            let mut expected = whole.decode(&bytes);
+            expected.extend(whole.flush());
+
+            let mut split = InputDecoder::new();
+            let mut events = vec![];
+            let mut rest = bytes.as_slice();
+            while !rest.is_empty() {
+                let n = (random() % 4 + 1).min(rest.len());
+                events.extend(split.decode(&rest[..n]));
+                rest = &rest[n..];
+            }
+            events.extend(split.flush());
+
+            assert_eq!(events, expected, "Input: {:?}", bytes);
+            assert_eq!(split.is_pending(), whole.is_pending());
+        }
+    }
+}
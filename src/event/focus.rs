@@ -0,0 +1,415 @@
+use crate::layout::{Rect, Side};
+
+use super::{Event, Key, KeyCode, KeyMod};
+
+/// Focusable item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusItem<I> {
+    pub id: I,
+    /// Item area, used for arrow keys traversal
+    pub rect: Option<Rect>,
+    /// Focus scope the item belongs to
+    pub scope: Option<String>,
+}
+
+/// Focus manager
+/// Keeps an ordered list of focusable ids and moves focus between them
+///
+/// - `Tab`/`BackTab` move focus to the next/previous item
+/// - arrow keys move focus to the closest item in that direction if [FocusManager::arrows] is enabled
+///   (items need rects, see [FocusManager::set_rect])
+/// - [FocusManager::trap] limits focus to a scope, for example for modals
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::event::*;
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Field { Title, Desc }
+///
+/// let mut focus = FocusManager::new()
+///     .item(Field::Title)
+///     .item(Field::Desc);
+///
+/// assert!(focus.is_focused(&Field::Title));
+///
+/// focus.handle_key(Key(KeyMod::NONE, KeyCode::Tab));
+/// assert!(focus.is_focused(&Field::Desc));
+///
+/// focus.handle_key(Key(KeyMod::NONE, KeyCode::Tab));
+/// assert!(focus.is_focused(&Field::Title), "Wraps around");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusManager<I> {
+    /// Whether to go to the first item after the last one and vice versa
+    pub wrap: bool,
+    /// Whether arrow keys move focus
+    pub arrows: bool,
+
+    items: Vec<FocusItem<I>>,
+    focused: Option<I>,
+    /// Trapped scopes with the focus to restore after releasing them
+    traps: Vec<(String, Option<I>)>,
+}
+impl<I: Clone + PartialEq> FocusManager<I> {
+    pub fn new() -> Self {
+        Self {
+            wrap: true,
+            arrows: false,
+            items: vec![],
+            focused: None,
+            traps: vec![],
+        }
+    }
+
+    //
+
+    /// Add a focusable item
+    pub fn item(mut self, id: I) -> Self {
+        self.add(id);
+        self
+    }
+    /// Add a focusable item into a scope
+    pub fn scoped_item<S: ToString>(mut self, scope: S, id: I) -> Self {
+        self.add_scoped(scope, id);
+        self
+    }
+    /// Set whether to go to the first item after the last one and vice versa
+    pub fn wrap(mut self, value: bool) -> Self {
+        self.wrap = value;
+        self
+    }
+    /// Set whether arrow keys move focus
+    pub fn arrows(mut self, value: bool) -> Self {
+        self.arrows = value;
+        self
+    }
+
+    /// Add a focusable item
+    /// The first added item gets focused
+    pub fn add(&mut self, id: I) {
+        self.push(FocusItem { id, rect: None, scope: None });
+    }
+    /// Add a focusable item into a scope
+    pub fn add_scoped<S: ToString>(&mut self, scope: S, id: I) {
+        self.push(FocusItem { id, rect: None, scope: Some(scope.to_string()) });
+    }
+    fn push(&mut self, item: FocusItem<I>) {
+        if self.focused.is_none() && self.is_reachable(&item) {
+            self.focused = Some(item.id.clone());
+        }
+        self.items.push(item);
+    }
+    /// Remove an item
+    /// If it was focused, focus moves to the next item or to the previous one if it was the last
+    pub fn remove(&mut self, id: &I) {
+        if self.is_focused(id) {
+            let items = self.reachable();
+            let index = items.iter().position(|item| &item.id == id);
+
+            self.focused = index
+                .and_then(|i| items.get(i + 1).or(i.checked_sub(1).and_then(|i| items.get(i))))
+                .map(|item| item.id.clone());
+        }
+        self.items.retain(|item| &item.id != id);
+    }
+    /// Set item area, used for arrow keys traversal
+    /// Returns `false` if there is no such item
+    pub fn set_rect(&mut self, id: &I, rect: Rect) -> bool {
+        match self.items.iter_mut().find(|item| &item.id == id) {
+            Some(item) => {
+                item.rect = Some(rect);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Returns `true` if the item can get focus now
+    fn is_reachable(&self, item: &FocusItem<I>) -> bool {
+        match self.traps.last() {
+            Some((scope, _)) => item.scope.as_ref() == Some(scope),
+            None => true
+        }
+    }
+    /// Items that can get focus now
+    fn reachable(&self) -> Vec<&FocusItem<I>> {
+        self.items
+            .iter()
+            .filter(|item| self.is_reachable(item))
+            .collect()
+    }
+
+    // Focus
+
+    /// Focus an item
+    /// Returns `false` if the item doesn't exist or is outside of the trapped scope
+    pub fn focus(&mut self, id: &I) -> bool {
+        if !self.items.iter().any(|item| &item.id == id && self.is_reachable(item)) {
+            return false;
+        }
+
+        self.focused = Some(id.clone());
+        true
+    }
+    /// Remove focus from all items
+    pub fn blur(&mut self) {
+        self.focused = None;
+    }
+    /// Focus the next item
+    /// Returns whether focus has changed
+    pub fn focus_next(&mut self) -> bool {
+        self.focus_by(1)
+    }
+    /// Focus the previous item
+    /// Returns whether focus has changed
+    pub fn focus_prev(&mut self) -> bool {
+        self.focus_by(-1)
+    }
+    /// Focus the first item
+    /// Returns whether focus has changed
+    pub fn focus_first(&mut self) -> bool {
+        let id = self.reachable().first().map(|item| item.id.clone());
+        self.set_focused(id)
+    }
+    /// Focus the last item
+    /// Returns whether focus has changed
+    pub fn focus_last(&mut self) -> bool {
+        let id = self.reachable().last().map(|item| item.id.clone());
+        self.set_focused(id)
+    }
+    /// Focus the closest item on the `side` of the focused one
+    /// Only [Side::Left], [Side::Top], [Side::Right] and [Side::Bottom] are supported
+    /// Returns whether focus has changed
+    pub fn focus_side(&mut self, side: Side) -> bool {
+        let Some(from) = self.focused_item().and_then(|item| item.rect) else {
+            return false;
+        };
+        let (fx, fy) = center(from);
+
+        let id = self.reachable()
+            .into_iter()
+            .filter(|item| Some(&item.id) != self.focused.as_ref())
+            .filter_map(|item| {
+                let (x, y) = center(item.rect?);
+                let (dx, dy) = (x - fx, y - fy);
+
+                // Distance along the side and offset across it
+                let (dist, offset) = match side {
+                    Side::Left => (-dx, dy),
+                    Side::Right => (dx, dy),
+                    Side::Top => (-dy, dx),
+                    Side::Bottom => (dy, dx),
+                    _ => return None,
+                };
+
+                (dist > 0).then_some((dist + offset.abs() * 2, &item.id))
+            })
+            .min_by_key(|(score, _)| *score)
+            .map(|(_, id)| id.clone());
+
+        match id {
+            Some(id) => self.set_focused(Some(id)),
+            None => false
+        }
+    }
+
+    fn focus_by(&mut self, step: isize) -> bool {
+        let items = self.reachable();
+        if items.is_empty() {
+            return false;
+        }
+
+        let len = items.len() as isize;
+        let cur = self.focused
+            .as_ref()
+            .and_then(|id| items.iter().position(|item| &item.id == id));
+
+        let index = match cur {
+            None if step > 0 => 0,
+            None => len - 1,
+            Some(cur) => {
+                let index = cur as isize + step;
+                if self.wrap {
+                    index.rem_euclid(len)
+                } else {
+                    index.clamp(0, len - 1)
+                }
+            }
+        };
+
+        let id = items[index as usize].id.clone();
+        self.set_focused(Some(id))
+    }
+    fn set_focused(&mut self, id: Option<I>) -> bool {
+        if id.is_none() || self.focused == id {
+            return false;
+        }
+
+        self.focused = id;
+        true
+    }
+
+    // Scopes
+
+    /// Trap focus inside `scope`, for example when a modal is opened
+    /// Focuses the first item of the scope
+    pub fn trap<S: ToString>(&mut self, scope: S) {
+        self.traps.push((scope.to_string(), self.focused.take()));
+        self.focus_first();
+    }
+    /// Release the last trapped scope and restore the focus that was before trapping
+    /// Returns `false` if nothing was trapped
+    pub fn release(&mut self) -> bool {
+        let Some((_, prev)) = self.traps.pop() else {
+            return false;
+        };
+
+        self.focused = prev;
+        true
+    }
+    /// Currently trapped scope
+    pub fn trapped_scope(&self) -> Option<&str> {
+        self.traps.last().map(|(scope, _)| scope.as_str())
+    }
+
+    // Events
+
+    /// Handle key events
+    /// Returns whether focus has changed
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key(KeyMod::NONE, KeyCode::Tab) => self.focus_next(),
+            Key(_, KeyCode::BackTab) => self.focus_prev(),
+            Key(KeyMod::SHIFT, KeyCode::Tab) => self.focus_prev(),
+
+            Key(KeyMod::NONE, KeyCode::Left) if self.arrows => self.focus_side(Side::Left),
+            Key(KeyMod::NONE, KeyCode::Right) if self.arrows => self.focus_side(Side::Right),
+            Key(KeyMod::NONE, KeyCode::Up) if self.arrows => self.focus_side(Side::Top),
+            Key(KeyMod::NONE, KeyCode::Down) if self.arrows => self.focus_side(Side::Bottom),
+
+            _ => false
+        }
+    }
+    /// Handle events
+    /// Returns whether focus has changed
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event.key_press() {
+            Some(key) => self.handle_key(key),
+            None => false
+        }
+    }
+
+    // Get
+
+    /// Focused item id
+    pub fn focused(&self) -> Option<&I> {
+        self.focused.as_ref()
+    }
+    fn focused_item(&self) -> Option<&FocusItem<I>> {
+        let id = self.focused.as_ref()?;
+        self.items.iter().find(|item| &item.id == id)
+    }
+    /// Returns `true` if the item has focus
+    pub fn is_focused(&self, id: &I) -> bool {
+        self.focused.as_ref() == Some(id)
+    }
+    /// All items in the traversal order
+    pub fn items(&self) -> &[FocusItem<I>] {
+        &self.items
+    }
+}
+impl<I: Clone + PartialEq> Default for FocusManager<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rect center in doubled coordinates, so it doesn't lose precision
+fn center(rect: Rect) -> (i32, i32) {
+    (
+        rect.x as i32 * 2 + rect.width as i32,
+        rect.y as i32 * 2 + rect.height as i32,
+    )
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab() -> Key { Key(KeyMod::NONE, KeyCode::Tab) }
+    fn back_tab() -> Key { Key(KeyMod::SHIFT, KeyCode::BackTab) }
+
+    #[test]
+    fn tab_traversal() {
+        let mut focus = FocusManager::new()
+            .item(1)
+            .item(2)
+            .item(3);
+
+        assert_eq!(focus.focused(), Some(&1));
+        assert!(focus.handle_key(tab()));
+        assert!(focus.handle_key(tab()));
+        assert_eq!(focus.focused(), Some(&3));
+        assert!(focus.handle_key(tab()));
+        assert_eq!(focus.focused(), Some(&1));
+        assert!(focus.handle_key(back_tab()));
+        assert_eq!(focus.focused(), Some(&3));
+
+        focus.wrap = false;
+        assert!(!focus.handle_key(tab()), "Doesn't wrap");
+        assert_eq!(focus.focused(), Some(&3));
+
+        focus.remove(&3);
+        assert_eq!(focus.focused(), Some(&2));
+        assert!(!focus.focus(&3));
+    }
+
+    #[test]
+    fn trap() {
+        let mut focus = FocusManager::new()
+            .item("list")
+            .item("search")
+            .scoped_item("modal", "ok")
+            .scoped_item("modal", "cancel");
+
+        focus.focus(&"search");
+        focus.trap("modal");
+        assert_eq!(focus.trapped_scope(), Some("modal"));
+        assert_eq!(focus.focused(), Some(&"ok"));
+
+        focus.handle_key(tab());
+        assert_eq!(focus.focused(), Some(&"cancel"));
+        focus.handle_key(tab());
+        assert_eq!(focus.focused(), Some(&"ok"), "Focus is trapped");
+        assert!(!focus.focus(&"list"));
+
+        assert!(focus.release());
+        assert_eq!(focus.focused(), Some(&"search"));
+        assert!(!focus.release());
+    }
+
+    #[test]
+    fn arrows() {
+        // [a][b]
+        // [c    ]
+        let mut focus = FocusManager::new()
+            .arrows(true)
+            .item('a')
+            .item('b')
+            .item('c');
+        focus.set_rect(&'a', Rect::new(0, 0, 10, 3));
+        focus.set_rect(&'b', Rect::new(10, 0, 10, 3));
+        focus.set_rect(&'c', Rect::new(0, 3, 20, 3));
+
+        assert!(focus.handle_key(Key(KeyMod::NONE, KeyCode::Right)));
+        assert_eq!(focus.focused(), Some(&'b'));
+        assert!(!focus.handle_key(Key(KeyMod::NONE, KeyCode::Right)));
+        assert!(focus.handle_key(Key(KeyMod::NONE, KeyCode::Down)));
+        assert_eq!(focus.focused(), Some(&'c'));
+        assert!(focus.handle_key(Key(KeyMod::NONE, KeyCode::Up)));
+        assert_eq!(focus.focused(), Some(&'a'), "Ties go to the first item");
+    }
+}
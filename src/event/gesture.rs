@@ -0,0 +1,337 @@
+use std::time::{Duration, Instant};
+
+use super::{Event, KeyMod, Mouse, MouseBtn};
+
+/// Mouse gesture
+/// Produced by [GestureTracker] from raw [Mouse] events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum Gesture {
+    /// Press and release without moving
+    Click(KeyMod, MouseBtn, u16, u16),
+    /// Second click in a row at the same place
+    DoubleClick(KeyMod, MouseBtn, u16, u16),
+    /// Third click in a row at the same place
+    TripleClick(KeyMod, MouseBtn, u16, u16),
+    /// Dragging has started
+    /// Contains the press position and the current position
+    DragStart(KeyMod, MouseBtn, (u16, u16), (u16, u16)),
+    /// Mouse moved while dragging
+    /// Contains the press position and the current position
+    DragMove(KeyMod, MouseBtn, (u16, u16), (u16, u16)),
+    /// Button released after dragging
+    /// Contains the press position and the release position
+    DragEnd(KeyMod, MouseBtn, (u16, u16), (u16, u16)),
+}
+impl Gesture {
+    /// Returns mouse button
+    pub fn btn(self) -> MouseBtn {
+        match self {
+            Self::Click(_, b, _, _) => b,
+            Self::DoubleClick(_, b, _, _) => b,
+            Self::TripleClick(_, b, _, _) => b,
+            Self::DragStart(_, b, _, _) => b,
+            Self::DragMove(_, b, _, _) => b,
+            Self::DragEnd(_, b, _, _) => b,
+        }
+    }
+    /// Returns key modifier
+    pub fn key_mod(self) -> KeyMod {
+        match self {
+            Self::Click(m, _, _, _) => m,
+            Self::DoubleClick(m, _, _, _) => m,
+            Self::TripleClick(m, _, _, _) => m,
+            Self::DragStart(m, _, _, _) => m,
+            Self::DragMove(m, _, _, _) => m,
+            Self::DragEnd(m, _, _, _) => m,
+        }
+    }
+    /// Returns current mouse position
+    pub fn pos(self) -> (u16, u16) {
+        match self {
+            Self::Click(_, _, x, y) => (x, y),
+            Self::DoubleClick(_, _, x, y) => (x, y),
+            Self::TripleClick(_, _, x, y) => (x, y),
+            Self::DragStart(_, _, _, pos) => pos,
+            Self::DragMove(_, _, _, pos) => pos,
+            Self::DragEnd(_, _, _, pos) => pos,
+        }
+    }
+    /// Returns the position where the gesture has started
+    /// Same as [Gesture::pos] for clicks
+    pub fn start(self) -> (u16, u16) {
+        match self {
+            Self::DragStart(_, _, start, _) => start,
+            Self::DragMove(_, _, start, _) => start,
+            Self::DragEnd(_, _, start, _) => start,
+            g => g.pos()
+        }
+    }
+    /// Returns the number of clicks in a row if this is a click
+    pub fn clicks(self) -> Option<u8> {
+        match self {
+            Self::Click(..) => Some(1),
+            Self::DoubleClick(..) => Some(2),
+            Self::TripleClick(..) => Some(3),
+            _ => None
+        }
+    }
+    /// Returns `true` if this is a drag gesture
+    pub fn is_drag(self) -> bool {
+        matches!(self, Self::DragStart(..) | Self::DragMove(..) | Self::DragEnd(..))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    mods: KeyMod,
+    btn: MouseBtn,
+    pos: (u16, u16),
+    dragging: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastClick {
+    btn: MouseBtn,
+    pos: (u16, u16),
+    time: Instant,
+    count: u8,
+}
+
+/// Mouse gesture tracker
+/// Turns raw [Mouse] events into clicks, double/triple clicks and drags
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::event::*;
+/// let mut tracker = GestureTracker::new();
+///
+/// assert_eq!(tracker.handle(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 2, 3)), None);
+/// assert_eq!(
+///     tracker.handle(Mouse::Up(KeyMod::NONE, MouseBtn::Left, 2, 3)),
+///     Some(Gesture::Click(KeyMod::NONE, MouseBtn::Left, 2, 3))
+/// );
+/// assert_eq!(tracker.handle(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 2, 3)), None);
+/// assert_eq!(
+///     tracker.handle(Mouse::Up(KeyMod::NONE, MouseBtn::Left, 2, 3)),
+///     Some(Gesture::DoubleClick(KeyMod::NONE, MouseBtn::Left, 2, 3))
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct GestureTracker {
+    /// Max time between clicks of a double or triple click
+    pub multi_click_time: Duration,
+    /// Max distance (in cells) between clicks of a double or triple click
+    pub multi_click_distance: u16,
+    /// Min distance (in cells) the mouse must move while pressed to start dragging
+    pub drag_distance: u16,
+
+    press: Option<Press>,
+    last_click: Option<LastClick>,
+}
+impl GestureTracker {
+    /// Default [GestureTracker::multi_click_time]
+    pub const DEFAULT_MULTI_CLICK_TIME: Duration = Duration::from_millis(500);
+
+    pub fn new() -> Self {
+        Self {
+            multi_click_time: Self::DEFAULT_MULTI_CLICK_TIME,
+            multi_click_distance: 1,
+            drag_distance: 1,
+            press: None,
+            last_click: None,
+        }
+    }
+
+    //
+
+    /// Set max time between clicks of a double or triple click
+    pub fn multi_click_time(mut self, time: Duration) -> Self {
+        self.multi_click_time = time;
+        self
+    }
+    /// Set max distance between clicks of a double or triple click
+    pub fn multi_click_distance(mut self, distance: u16) -> Self {
+        self.multi_click_distance = distance;
+        self
+    }
+    /// Set min distance the mouse must move while pressed to start dragging
+    pub fn drag_distance(mut self, distance: u16) -> Self {
+        self.drag_distance = distance;
+        self
+    }
+
+    /// Forget the pressed button and previous clicks
+    pub fn reset(&mut self) {
+        self.press = None;
+        self.last_click = None;
+    }
+    /// Returns `true` if a button is being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.press.is_some_and(|p| p.dragging)
+    }
+
+    /// Handle an event
+    /// Returns a gesture if the event is a mouse event that completes one
+    pub fn handle_event(&mut self, event: &Event) -> Option<Gesture> {
+        match event {
+            Event::Mouse(mouse, _, _) => self.handle(*mouse),
+            _ => None
+        }
+    }
+    /// Handle a mouse event
+    /// Returns a gesture if the event completes one
+    pub fn handle(&mut self, mouse: Mouse) -> Option<Gesture> {
+        self.handle_at(mouse, Instant::now())
+    }
+    /// Same as [GestureTracker::handle], but with a custom current time
+    pub fn handle_at(&mut self, mouse: Mouse, now: Instant) -> Option<Gesture> {
+        match mouse {
+            Mouse::Down(mods, btn, x, y) => {
+                self.press = Some(Press { mods, btn, pos: (x, y), dragging: false });
+                None
+            },
+            Mouse::Drag(mods, btn, x, y) => {
+                let press = match self.press {
+                    Some(press) if press.btn == btn => press,
+                    // The press may be missed, for example if it happened outside of the terminal
+                    _ => {
+                        self.press = Some(Press { mods, btn, pos: (x, y), dragging: true });
+                        self.last_click = None;
+                        return Some(Gesture::DragStart(mods, btn, (x, y), (x, y)));
+                    }
+                };
+
+                if press.dragging {
+                    return Some(Gesture::DragMove(press.mods, btn, press.pos, (x, y)));
+                }
+
+                if distance(press.pos, (x, y)) < self.drag_distance {
+                    return None;
+                }
+
+                self.press = Some(Press { dragging: true, ..press });
+                self.last_click = None;
+                Some(Gesture::DragStart(press.mods, btn, press.pos, (x, y)))
+            },
+            Mouse::Up(_, btn, x, y) => {
+                let press = self.press.take();
+
+                match press {
+                    Some(press) if press.btn == btn && press.dragging => {
+                        Some(Gesture::DragEnd(press.mods, btn, press.pos, (x, y)))
+                    },
+                    Some(press) if press.btn == btn => {
+                        Some(self.click(press.mods, btn, press.pos, now))
+                    },
+                    // Release without a press
+                    _ => {
+                        self.last_click = None;
+                        None
+                    }
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn click(&mut self, mods: KeyMod, btn: MouseBtn, pos: (u16, u16), now: Instant) -> Gesture {
+        let count = match self.last_click {
+            Some(last) if last.btn == btn
+                && last.count < 3
+                && now.saturating_duration_since(last.time) <= self.multi_click_time
+                && distance(last.pos, pos) <= self.multi_click_distance
+                => last.count + 1,
+            _ => 1
+        };
+
+        self.last_click = Some(LastClick { btn, pos, time: now, count });
+
+        let (x, y) = pos;
+        match count {
+            1 => Gesture::Click(mods, btn, x, y),
+            2 => Gesture::DoubleClick(mods, btn, x, y),
+            _ => Gesture::TripleClick(mods, btn, x, y),
+        }
+    }
+}
+impl Default for GestureTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Chebyshev distance between two cells
+fn distance(a: (u16, u16), b: (u16, u16)) -> u16 {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M: KeyMod = KeyMod::NONE;
+    const L: MouseBtn = MouseBtn::Left;
+
+    #[test]
+    fn clicks() {
+        let mut t = GestureTracker::new();
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+
+        let click = |t: &mut GestureTracker, at: Duration, x: u16| {
+            t.handle_at(Mouse::Down(M, L, x, 0), now + at);
+            t.handle_at(Mouse::Up(M, L, x, 0), now + at)
+        };
+
+        assert_eq!(click(&mut t, ms(0), 1), Some(Gesture::Click(M, L, 1, 0)));
+        assert_eq!(click(&mut t, ms(100), 2), Some(Gesture::DoubleClick(M, L, 2, 0)));
+        assert_eq!(click(&mut t, ms(200), 2), Some(Gesture::TripleClick(M, L, 2, 0)));
+        assert_eq!(click(&mut t, ms(300), 2), Some(Gesture::Click(M, L, 2, 0)), "Starts over after a triple click");
+
+        assert_eq!(click(&mut t, ms(1000), 2), Some(Gesture::Click(M, L, 2, 0)), "Too late");
+        assert_eq!(click(&mut t, ms(1100), 10), Some(Gesture::Click(M, L, 10, 0)), "Too far");
+
+        t.handle_at(Mouse::Down(M, MouseBtn::Right, 10, 0), now + ms(1200));
+        assert_eq!(
+            t.handle_at(Mouse::Up(M, MouseBtn::Right, 10, 0), now + ms(1200)),
+            Some(Gesture::Click(M, MouseBtn::Right, 10, 0)),
+            "Other button"
+        );
+    }
+
+    #[test]
+    fn drag() {
+        let mut t = GestureTracker::new().drag_distance(2);
+
+        assert_eq!(t.handle(Mouse::Down(M, L, 5, 5)), None);
+        assert_eq!(t.handle(Mouse::Drag(M, L, 6, 5)), None, "Below the threshold");
+        assert!(!t.is_dragging());
+        assert_eq!(t.handle(Mouse::Drag(M, L, 7, 5)), Some(Gesture::DragStart(M, L, (5, 5), (7, 5))));
+        assert!(t.is_dragging());
+        assert_eq!(t.handle(Mouse::Drag(M, L, 5, 5)), Some(Gesture::DragMove(M, L, (5, 5), (5, 5))));
+        assert_eq!(t.handle(Mouse::Up(M, L, 9, 6)), Some(Gesture::DragEnd(M, L, (5, 5), (9, 6))));
+        assert!(!t.is_dragging());
+
+        // Press outside of the terminal
+        assert_eq!(t.handle(Mouse::Drag(M, L, 1, 1)), Some(Gesture::DragStart(M, L, (1, 1), (1, 1))));
+        assert_eq!(t.handle(Mouse::Drag(M, L, 2, 1)), Some(Gesture::DragMove(M, L, (1, 1), (2, 1))));
+    }
+
+    #[test]
+    fn drag_is_not_click() {
+        let mut t = GestureTracker::new();
+
+        t.handle(Mouse::Down(M, L, 0, 0));
+        t.handle(Mouse::Up(M, L, 0, 0));
+        t.handle(Mouse::Down(M, L, 0, 0));
+        t.handle(Mouse::Drag(M, L, 3, 0));
+        t.handle(Mouse::Up(M, L, 3, 0));
+        t.handle(Mouse::Down(M, L, 0, 0));
+
+        assert_eq!(t.handle(Mouse::Up(M, L, 0, 0)), Some(Gesture::Click(M, L, 0, 0)));
+    }
+}
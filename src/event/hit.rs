@@ -0,0 +1,220 @@
+use crate::{buffer::Buffer, layout::Rect, widget::Draw};
+
+use super::{Event, Mouse};
+
+/// Hit-test region
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub struct HitRegion<I> {
+    pub id: I,
+    pub rect: Rect,
+    /// Regions with higher `z` are above regions with lower `z`
+    /// Of regions with the same `z` the last registered one is above
+    pub z: i32,
+}
+
+/// Hover change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hover<I> {
+    /// The mouse entered the region with this id
+    Enter(I),
+    /// The mouse left the region with this id
+    Leave(I),
+}
+
+/// Hit-test map
+/// Remembers which widget owns which area of the screen, so mouse events can be routed to widgets
+///
+/// Clear it before drawing a frame and register widget rects while drawing.
+/// After drawing call [HitMap::update_hover] to get hover changes caused by the new layout
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::{event::*, layout::Rect};
+/// let mut hits = HitMap::new();
+///
+/// hits.clear();
+/// hits.register("list", Rect::new(0, 0, 20, 10), 0);
+/// hits.register("popup", Rect::new(5, 5, 10, 3), 1);
+///
+/// assert_eq!(hits.hit((1, 1)), Some(&"list"));
+/// assert_eq!(hits.hit((6, 6)), Some(&"popup"));
+/// assert_eq!(hits.hit((30, 1)), None);
+///
+/// let changes = hits.handle_mouse(Mouse::Move(KeyMod::NONE, 6, 6));
+/// assert_eq!(changes, vec![Hover::Enter("popup")]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitMap<I> {
+    regions: Vec<HitRegion<I>>,
+    hovered: Option<I>,
+    mouse_pos: Option<(u16, u16)>,
+}
+impl<I> HitMap<I> {
+    pub fn new() -> Self {
+        Self {
+            regions: vec![],
+            hovered: None,
+            mouse_pos: None,
+        }
+    }
+
+    //
+
+    /// Remove all regions
+    /// Call it before drawing a new frame
+    /// The hovered region and the mouse position are kept
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+    /// Register a region
+    pub fn register(&mut self, id: I, rect: Rect, z: i32) {
+        self.regions.push(HitRegion { id, rect, z });
+    }
+    /// Draw a widget and register its drawn rect
+    /// Returns the drawn rect
+    pub fn draw<W: Draw>(&mut self, id: I, z: i32, widget: W, buf: &mut Buffer, rect: Rect) -> Rect {
+        let rect = widget.draw(buf, rect);
+        self.register(id, rect, z);
+        rect
+    }
+
+    /// Returns the id of the topmost region at `pos`
+    pub fn hit(&self, pos: (u16, u16)) -> Option<&I> {
+        self.hit_region(pos).map(|r| &r.id)
+    }
+    /// Returns the topmost region at `pos`
+    pub fn hit_region(&self, pos: (u16, u16)) -> Option<&HitRegion<I>> {
+        self.regions
+            .iter()
+            .filter(|r| r.rect.contains(pos))
+            // `max_by_key` returns the last max element, which was registered last
+            .max_by_key(|r| r.z)
+    }
+    /// Returns ids of all regions at `pos`, from the topmost to the bottommost
+    pub fn hit_all(&self, pos: (u16, u16)) -> Vec<&I> {
+        let mut regions: Vec<_> = self.regions
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.rect.contains(pos))
+            .collect();
+
+        regions.sort_by_key(|(index, r)| std::cmp::Reverse((r.z, *index)));
+        regions.into_iter().map(|(_, r)| &r.id).collect()
+    }
+    /// Returns the id of the topmost region under the mouse event position
+    pub fn hit_mouse(&self, mouse: Mouse) -> Option<&I> {
+        self.hit(mouse.pos()?)
+    }
+
+    /// Registered regions in the registration order
+    pub fn regions(&self) -> &[HitRegion<I>] {
+        &self.regions
+    }
+    /// Id of the region under the mouse
+    pub fn hovered(&self) -> Option<&I> {
+        self.hovered.as_ref()
+    }
+    /// Last known mouse position
+    pub fn mouse_pos(&self) -> Option<(u16, u16)> {
+        self.mouse_pos
+    }
+}
+impl<I: Clone + PartialEq> HitMap<I> {
+    /// Returns the rect of the topmost region with `id`
+    pub fn rect(&self, id: &I) -> Option<Rect> {
+        self.regions
+            .iter()
+            .filter(|r| &r.id == id)
+            .max_by_key(|r| r.z)
+            .map(|r| r.rect)
+    }
+
+    /// Handle an event
+    /// Returns hover changes if this is a mouse event
+    pub fn handle_event(&mut self, event: &Event) -> Vec<Hover<I>> {
+        match event {
+            Event::Mouse(mouse, _, _) => self.handle_mouse(*mouse),
+            _ => vec![]
+        }
+    }
+    /// Remember the mouse position and update the hovered region
+    /// Returns hover changes, `Leave` always goes before `Enter`
+    pub fn handle_mouse(&mut self, mouse: Mouse) -> Vec<Hover<I>> {
+        if let Some(pos) = mouse.pos() {
+            self.mouse_pos = Some(pos);
+        }
+        self.update_hover()
+    }
+    /// Update the hovered region using the last known mouse position
+    /// Call it after drawing a frame, since the regions under the mouse may have changed
+    /// Returns hover changes, `Leave` always goes before `Enter`
+    pub fn update_hover(&mut self) -> Vec<Hover<I>> {
+        let hovered = self.mouse_pos
+            .and_then(|pos| self.hit(pos))
+            .cloned();
+
+        if hovered == self.hovered {
+            return vec![];
+        }
+
+        let prev = std::mem::replace(&mut self.hovered, hovered.clone());
+        prev.map(Hover::Leave)
+            .into_iter()
+            .chain(hovered.map(Hover::Enter))
+            .collect()
+    }
+}
+impl<I> Default for HitMap<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use crate::event::KeyMod;
+
+    use super::*;
+
+    #[test]
+    fn hit() {
+        let mut hits = HitMap::new();
+        hits.register(1, Rect::new(0, 0, 10, 10), 0);
+        hits.register(2, Rect::new(2, 2, 4, 4), 0);
+        hits.register(3, Rect::new(0, 0, 3, 3), 5);
+        hits.register(4, Rect::new(0, 0, 10, 10), -1);
+
+        assert_eq!(hits.hit((8, 8)), Some(&1));
+        assert_eq!(hits.hit((4, 4)), Some(&2), "Registered later");
+        assert_eq!(hits.hit((2, 2)), Some(&3), "Higher z");
+        assert_eq!(hits.hit((10, 10)), None);
+        assert_eq!(hits.hit_all((2, 2)), vec![&3, &2, &1, &4]);
+        assert_eq!(hits.hit_mouse(Mouse::Down(KeyMod::NONE, Default::default(), 5, 5)), Some(&2));
+        assert_eq!(hits.rect(&2), Some(Rect::new(2, 2, 4, 4)));
+    }
+
+    #[test]
+    fn hover() {
+        let mut hits = HitMap::new();
+        hits.register('a', Rect::new(0, 0, 5, 1), 0);
+        hits.register('b', Rect::new(5, 0, 5, 1), 0);
+
+        assert_eq!(hits.handle_mouse(Mouse::Move(KeyMod::NONE, 1, 0)), vec![Hover::Enter('a')]);
+        assert_eq!(hits.handle_mouse(Mouse::Move(KeyMod::NONE, 2, 0)), vec![]);
+        assert_eq!(hits.handle_mouse(Mouse::Move(KeyMod::NONE, 6, 0)), vec![Hover::Leave('a'), Hover::Enter('b')]);
+        assert_eq!(hits.hovered(), Some(&'b'));
+
+        // Next frame, the layout has changed under the mouse
+        hits.clear();
+        hits.register('a', Rect::new(0, 0, 10, 1), 1);
+        assert_eq!(hits.update_hover(), vec![Hover::Leave('b'), Hover::Enter('a')]);
+
+        hits.clear();
+        assert_eq!(hits.update_hover(), vec![Hover::Leave('a')]);
+        assert_eq!(hits.hovered(), None);
+    }
+}
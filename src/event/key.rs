@@ -1,3 +1,5 @@
+use std::{error::Error, fmt::{self, Display}, str::FromStr};
+
 use super::KeyMod;
 
 /// Modifier key code
@@ -7,14 +9,17 @@ pub enum ModKeyCode {
     Shift,
     Ctrl,
     Alt,
-    /// Meta/win/super key
+    /// Win/super key
     Super,
+    Hyper,
+    Meta,
     Unknown
 }
 
 /// Key code
+///
+/// Can be parsed from a string and displayed (see [KeyCode::from_str])
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
 pub enum KeyCode {
     Backspace,
     Enter,
@@ -66,14 +71,10 @@ pub enum KeyCode {
     KeypadBegin,
 
     #[default]
-    #[cfg_attr(feature="serde", serde(alias=""))]
     None,
     Unknown,
 
-    #[cfg_attr(feature="serde", serde(untagged, deserialize_with="deserialize_char_key_code"))]
     Char(char),
-    /// TODO: create custom deserializer/serializer
-    #[cfg_attr(feature="serde", serde(untagged, deserialize_with="deserialize_f_key_code"))]
     F(u8),
     /// A modifier key
     ///
@@ -81,13 +82,78 @@ pub enum KeyCode {
     /// [`KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES`] and
     /// [`KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES`] have been enabled with
     /// [`PushKeyboardEnhancementFlags`].
-    #[cfg_attr(feature="serde", serde(untagged))]
     Mod(ModKeyCode),
 }
 
+/// Key event kind
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum KeyKind {
+    #[default]
+    Press,
+    /// **For [CrosstermBackend]:** this kind can only be read if
+    /// [`KeyboardEnhancementFlags::REPORT_EVENT_TYPES`] has been enabled with
+    /// [`PushKeyboardEnhancementFlags`].
+    Repeat,
+    /// **For [CrosstermBackend]:** this kind can only be read if
+    /// [`KeyboardEnhancementFlags::REPORT_EVENT_TYPES`] has been enabled with
+    /// [`PushKeyboardEnhancementFlags`].
+    Release,
+}
+
+/// Key lock states
+///
+/// **For [CrosstermBackend]:** these states can only be read if
+/// [`KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES`] has been enabled with
+/// [`PushKeyboardEnhancementFlags`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case", default))]
+pub struct KeyState {
+    /// Whether the key is on the keypad
+    pub keypad: bool,
+    pub caps_lock: bool,
+    pub num_lock: bool,
+}
+
+/// Key event info
+/// Additional info about a key event, such as its kind and lock states
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case", default))]
+pub struct KeyInfo {
+    pub kind: KeyKind,
+    pub state: KeyState,
+}
+impl KeyInfo {
+    pub fn new(kind: KeyKind, state: KeyState) -> Self {
+        Self { kind, state }
+    }
+
+    //
+
+    /// Returns `true` if the key was pressed or repeated
+    pub fn is_press(&self) -> bool {
+        matches!(self.kind, KeyKind::Press | KeyKind::Repeat)
+    }
+    /// Returns `true` if the key was released
+    pub fn is_release(&self) -> bool {
+        self.kind == KeyKind::Release
+    }
+}
+impl From<KeyKind> for KeyInfo {
+    fn from(value: KeyKind) -> Self {
+        Self::new(value, KeyState::default())
+    }
+}
+
 /// Key event
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+///
+/// Can be parsed from a string and displayed (see [Key::from_str])
+///
+/// # Notes
+///
+/// - keys are compared using [KeyMod::matches], so a key with [KeyMod::ANY] is equal to a key
+///   with the same code and any modifiers
+#[derive(Debug, Clone, Copy, Eq)]
 pub struct Key(pub KeyMod, pub KeyCode);
 
 impl Key {
@@ -99,6 +165,24 @@ impl Key {
     pub fn code(self) -> KeyCode {
         self.1
     }
+    /// Returns the key with shift folded into the character
+    /// For example `shift+a` and `shift+A` become `A`, chars without case (like `shift+1`) keep shift
+    ///
+    /// Terminals report shifted characters differently, use this to compare them reliably
+    pub fn normalized(self) -> Self {
+        match self {
+            Self(m, KeyCode::Char(c)) if m.shift() && (c.is_lowercase() || c.is_uppercase()) => {
+                let c = c.to_uppercase().next().unwrap_or(c);
+                Self(m - KeyMod::SHIFT, KeyCode::Char(c))
+            },
+            key => key
+        }
+    }
+}
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.matches(other.0) && self.1 == other.1
+    }
 }
 impl<M, C> From<(M, C)> for Key
 where M: Into<KeyMod>,
@@ -109,84 +193,428 @@ where M: Into<KeyMod>,
     }
 }
 
-#[cfg(feature="serde")]
-struct KeyCodeFVisitor;
-#[cfg(feature="serde")]
-impl<'de> serde::de::Visitor<'de> for KeyCodeFVisitor {
-    type Value = u8;
+/// Key parse error
+/// Returned when a key, key code or key modifier string can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError(String);
+impl ParseKeyError {
+    pub fn new<S: ToString>(msg: S) -> Self {
+        Self(msg.to_string())
+    }
+}
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl Error for ParseKeyError {}
+
+impl ModKeyCode {
+    /// Every modifier key code with its name
+    pub const NAMED: [(Self, &'static str); 7] = [
+        (Self::Shift, "shift"),
+        (Self::Ctrl, "ctrl"),
+        (Self::Alt, "alt"),
+        (Self::Super, "super"),
+        (Self::Hyper, "hyper"),
+        (Self::Meta, "meta"),
+        (Self::Unknown, "unknown"),
+    ];
+}
+impl Display for ModKeyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = Self::NAMED
+            .into_iter()
+            .find(|(m, _)| m == self)
+            .map_or("unknown", |(_, n)| n);
 
-    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "f1, f2, ..., f12")
+        write!(f, "{}", name)
     }
+}
 
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-    where E: serde::de::Error
-    {
-        if let Some(n) = v.strip_prefix("f") {
-            let Ok(n) = n.parse::<u8>() else {
-                return Err(E::custom(format!("expected f1, f2, ..., f12, but {} was received", v)));
-            };
-            if n > 12 {
-                return Err(E::custom(format!("expected f1, f2, ..., f12, but {} was received", v)));
+impl KeyCode {
+    /// Every named key code with its name
+    pub const NAMED: [(Self, &'static str); 24] = [
+        (Self::Backspace, "backspace"),
+        (Self::Enter, "enter"),
+        (Self::Tab, "tab"),
+        (Self::BackTab, "back_tab"),
+        (Self::Delete, "delete"),
+        (Self::Left, "left"),
+        (Self::Right, "right"),
+        (Self::Up, "up"),
+        (Self::Down, "down"),
+        (Self::Home, "home"),
+        (Self::End, "end"),
+        (Self::PageUp, "page_up"),
+        (Self::PageDown, "page_down"),
+        (Self::Insert, "insert"),
+        (Self::Esc, "esc"),
+        (Self::CapsLock, "caps_lock"),
+        (Self::ScrollLock, "scroll_lock"),
+        (Self::NumLock, "num_lock"),
+        (Self::PrintScreen, "print_screen"),
+        (Self::Pause, "pause"),
+        (Self::Menu, "menu"),
+        (Self::KeypadBegin, "keypad_begin"),
+        (Self::None, "none"),
+        (Self::Unknown, "unknown"),
+    ];
+    /// Alternative names of key codes
+    /// Only used for parsing
+    pub const ALIASES: [(Self, &'static str); 17] = [
+        (Self::Enter, "return"),
+        (Self::Enter, "cr"),
+        (Self::Backspace, "bs"),
+        (Self::Delete, "del"),
+        (Self::Insert, "ins"),
+        (Self::Esc, "escape"),
+        (Self::PageUp, "pgup"),
+        (Self::PageDown, "pgdn"),
+        (Self::Char(' '), "space"),
+        (Self::Char('+'), "plus"),
+        (Self::Char('-'), "minus"),
+        (Self::Char('<'), "lt"),
+        (Self::Char('>'), "gt"),
+        (Self::Char('|'), "bar"),
+        (Self::Char('\\'), "bslash"),
+        (Self::Char('\\'), "backslash"),
+        (Self::Mod(ModKeyCode::Ctrl), "control"),
+    ];
+    /// Max number of the function key (`F1`, `F2`, ..., `F255`)
+    /// Terminals report keys up to `F35`, so every [KeyCode::F] they send can be parsed back
+    pub const MAX_F: u8 = u8::MAX;
+}
+impl Display for KeyCode {
+    /// Writes the key code name in `snake_case`, for example `page_up`, `f4` or `a`
+    /// Space, `+` and `-` are written as `space`, `plus` and `minus`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(' ') => write!(f, "space"),
+            Self::Char('+') => write!(f, "plus"),
+            Self::Char('-') => write!(f, "minus"),
+            Self::Char(char) => write!(f, "{}", char),
+            Self::F(n) => write!(f, "f{}", n),
+            Self::Mod(code) => write!(f, "{}", code),
+            code => {
+                let name = Self::NAMED
+                    .into_iter()
+                    .find(|(c, _)| c == code)
+                    .map_or("unknown", |(_, n)| n);
+
+                write!(f, "{}", name)
             }
+        }
+    }
+}
+impl FromStr for KeyCode {
+    type Err = ParseKeyError;
+
+    /// Parses a key code
+    ///
+    /// - a single character is parsed as [KeyCode::Char] (case-sensitive)
+    /// - names are case-insensitive and `_` is optional, so `page_up`, `PageUp` and `pgup` are the same
+    /// - `f1`, `f2`, ..., `f255` are parsed as [KeyCode::F]
+    /// - an empty string is parsed as [KeyCode::None]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tuich::event::*;
+    /// assert_eq!("a".parse(), Ok(KeyCode::Char('a')));
+    /// assert_eq!("Esc".parse(), Ok(KeyCode::Esc));
+    /// assert_eq!("page_up".parse(), Ok(KeyCode::PageUp));
+    /// assert_eq!("F4".parse(), Ok(KeyCode::F(4)));
+    /// assert_eq!("space".parse(), Ok(KeyCode::Char(' ')));
+    /// assert!("foo".parse::<KeyCode>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => return Ok(Self::None),
+            (Some(char), None) => return Ok(Self::Char(char)),
+            _ => ()
+        }
+
+        let name = s.to_lowercase().replace('_', "");
+        let find = |list: &[(Self, &str)]| {
+            list
+                .iter()
+                .find(|(_, n)| n.replace('_', "") == name)
+                .map(|(c, _)| *c)
+        };
 
-            return Ok(n)
+        if let Some(code) = find(&Self::NAMED).or_else(|| find(&Self::ALIASES)) {
+            return Ok(code);
+        }
+        if let Some((code, _)) = ModKeyCode::NAMED.into_iter().find(|(_, n)| n.eq(&name)) {
+            return Ok(Self::Mod(code));
+        }
+        if let Some(n) = name.strip_prefix('f') {
+            return match n.parse::<u8>() {
+                Ok(n) if (1..=Self::MAX_F).contains(&n) => Ok(Self::F(n)),
+                _ => Err(ParseKeyError::new(format!("expected f1, f2, ..., f{}, but {} was received", Self::MAX_F, s)))
+            };
         }
 
-        Err(E::custom(format!("expected f1, f2, ..., f12, but {} was received", v)))
+        Err(ParseKeyError::new(format!("unknown key \"{}\"", s)))
+    }
+}
+
+impl Display for Key {
+    /// Writes the key in `mods+code` notation, for example `ctrl+shift+a`
+    /// Keys with [KeyMod::NONE] are written without modifiers
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            write!(f, "{}", self.1)
+        } else {
+            write!(f, "{}+{}", self.0, self.1)
+        }
+    }
+}
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    /// Parses a key in one of the human notations
+    ///
+    /// - `ctrl+shift+a`, `alt+f4` - modifier names and the key code joined with `+`
+    /// - `C-x`, `M-x` - emacs style, modifiers joined with `-`
+    /// - `<Esc>`, `<C-x>` - vim style, same as above but surrounded with `<` and `>`
+    ///
+    /// Keys without modifiers are parsed with [KeyMod::NONE], use `any+a` for [KeyMod::ANY]
+    /// See [KeyMod::from_name] and [KeyCode::from_str] for the list of names
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tuich::event::*;
+    /// assert_eq!("ctrl+shift+a".parse(), Ok(Key(KeyMod::CTRL | KeyMod::SHIFT, KeyCode::Char('a'))));
+    /// assert_eq!("alt+f4".parse(), Ok(Key(KeyMod::ALT, KeyCode::F(4))));
+    /// assert_eq!("C-x".parse(), Ok(Key(KeyMod::CTRL, KeyCode::Char('x'))));
+    /// assert_eq!("<Esc>".parse(), Ok(Key(KeyMod::NONE, KeyCode::Esc)));
+    /// assert_eq!("ctrl++".parse(), Ok(Key(KeyMod::CTRL, KeyCode::Char('+'))));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = if s.len() > 2 && s.starts_with('<') && s.ends_with('>') {
+            &s[1..s.len() - 1]
+        } else {
+            s
+        };
+
+        let is_sep = |c: char| c == '+' || c == '-';
+
+        // Split at the last separator, but treat a trailing separator as the key itself (`ctrl++`)
+        let (mods, code) = match s.char_indices().last() {
+            None => return Ok(Self(KeyMod::NONE, KeyCode::None)),
+            Some((0, _)) => (None, s),
+            Some((i, c)) if is_sep(c) => {
+                let mods = &s[..i];
+                match mods.strip_suffix(is_sep) {
+                    Some(mods) => (Some(mods), &s[i..]),
+                    None => return Err(ParseKeyError::new(format!("missing key code after modifiers \"{}\"", s)))
+                }
+            },
+            Some(_) => match s.rfind(is_sep) {
+                Some(i) => (Some(&s[..i]), &s[i + 1..]),
+                None => (None, s)
+            }
+        };
+
+        let mods = match mods {
+            None | Some("") => KeyMod::NONE,
+            Some(mods) => mods.parse()?
+        };
+
+        Ok(Self(mods, code.parse()?))
     }
 }
 
 #[cfg(feature="serde")]
-struct KeyCodeCharVisitor;
+impl serde::Serialize for KeyCode {
+    /// Serializes using [Display]
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
+}
 #[cfg(feature="serde")]
-impl<'de> serde::de::Visitor<'de> for KeyCodeCharVisitor {
-    type Value = char;
+impl<'de> serde::Deserialize<'de> for KeyCode {
+    /// Deserializes from a string using [FromStr] or from a number 0-9
+    fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+        des.deserialize_any(KeyCodeVisitor)
+    }
+}
 
-    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "character or number 0-9")
+#[cfg(feature="serde")]
+struct KeyCodeVisitor;
+#[cfg(feature="serde")]
+impl<'de> serde::de::Visitor<'de> for KeyCodeVisitor {
+    type Value = KeyCode;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "key name, character or number 0-9")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where E: serde::de::Error
     {
-        let mut chars = v.chars();
-        let Some(char) = chars.next() else {
-            return Err(E::custom("expected a character"));
-        };
-        if chars.next().is_some() {
-            return Err(E::custom(format!("expected only one character, got {}", v)));
-        }
-
-        Ok(char)
+        v.parse().map_err(E::custom)
     }
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
     where E: serde::de::Error
     {
-        if v < 0 || v > 9 {
+        if !(0..=9).contains(&v) {
             return Err(E::custom(format!("unexpected number character {}, must be 0-9", v)))
         }
 
-        Ok(v.to_string().chars().nth(0).unwrap())
+        Ok(KeyCode::Char(char::from(b'0' + v as u8)))
+    }
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where E: serde::de::Error
+    {
+        self.visit_i64(v.min(i64::MAX as u64) as i64)
     }
 }
 
 #[cfg(feature="serde")]
-fn deserialize_f_key_code<'de, D>(des: D) -> Result<u8, D::Error>
-where D: serde::Deserializer<'de>
-{
-    des.deserialize_str(KeyCodeFVisitor)
+impl serde::Serialize for Key {
+    /// Serializes using [Display], for example `ctrl+a`
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
 }
 #[cfg(feature="serde")]
-fn deserialize_char_key_code<'de, D>(des: D) -> Result<char, D::Error>
-where D: serde::Deserializer<'de>
-{
-    des.deserialize_any(KeyCodeCharVisitor)
+impl<'de> serde::Deserialize<'de> for Key {
+    /// Deserializes from a string using [FromStr]
+    /// Also accepts the old array form `[modifiers, code]`, for example `["shift_ctrl", "a"]`
+    fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+        des.deserialize_any(KeyVisitor)
+    }
+}
+
+#[cfg(feature="serde")]
+struct KeyVisitor;
+#[cfg(feature="serde")]
+impl<'de> serde::de::Visitor<'de> for KeyVisitor {
+    type Value = Key;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "key, for example \"ctrl+a\", or an array [modifiers, code]")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where E: serde::de::Error
+    {
+        v.parse().map_err(E::custom)
+    }
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where A: serde::de::SeqAccess<'de>
+    {
+        use serde::de::Error;
+
+        let Some(mods) = seq.next_element::<KeyMod>()? else {
+            return Err(A::Error::invalid_length(0, &self));
+        };
+        let Some(code) = seq.next_element::<KeyCode>()? else {
+            return Err(A::Error::invalid_length(1, &self));
+        };
+        if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+            return Err(A::Error::invalid_length(3, &self));
+        }
+
+        Ok(Key(mods, code))
+    }
 }
 
 
 
 // Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_key() {
+        assert_eq!(key("a"), Key(KeyMod::NONE, KeyCode::Char('a')));
+        assert_eq!(key("A"), Key(KeyMod::NONE, KeyCode::Char('A')));
+        assert_eq!(key("ctrl+shift+a"), Key(KeyMod::CTRL | KeyMod::SHIFT, KeyCode::Char('a')));
+        assert_eq!(key("Ctrl+Alt+Delete"), Key(KeyMod::CTRL_ALT, KeyCode::Delete));
+        assert_eq!(key("alt+f4"), Key(KeyMod::ALT, KeyCode::F(4)));
+        assert_eq!(key("super+hyper+meta+x"), Key(KeyMod::SUPER | KeyMod::HYPER | KeyMod::META, KeyCode::Char('x')));
+        assert_eq!(key("C-x"), Key(KeyMod::CTRL, KeyCode::Char('x')));
+        assert_eq!(key("M-x"), Key(KeyMod::ALT, KeyCode::Char('x')));
+        assert_eq!(key("C-M-S-x"), Key(KeyMod::CTRL | KeyMod::ALT | KeyMod::SHIFT, KeyCode::Char('x')));
+        assert_eq!(key("s-x"), Key(KeyMod::SUPER, KeyCode::Char('x')));
+        assert_eq!(key("<Esc>"), Key(KeyMod::NONE, KeyCode::Esc));
+        assert_eq!(key("<CR>"), Key(KeyMod::NONE, KeyCode::Enter));
+        assert_eq!(key("<C-Space>"), Key(KeyMod::CTRL, KeyCode::Char(' ')));
+        assert_eq!(key("<S-Tab>"), Key(KeyMod::SHIFT, KeyCode::Tab));
+        assert_eq!(key("<"), Key(KeyMod::NONE, KeyCode::Char('<')));
+        assert_eq!(key("+"), Key(KeyMod::NONE, KeyCode::Char('+')));
+        assert_eq!(key("ctrl++"), Key(KeyMod::CTRL, KeyCode::Char('+')));
+        assert_eq!(key("C--"), Key(KeyMod::CTRL, KeyCode::Char('-')));
+        assert_eq!(key("any+q").key_mod(), KeyMod::ANY);
+
+        assert!("".parse::<Key>().is_ok_and(|k| k.code() == KeyCode::None));
+        assert!("ctrl+".parse::<Key>().is_err());
+        assert!("foo+a".parse::<Key>().is_err());
+        assert!("ctrl+foo".parse::<Key>().is_err());
+        assert!("f256".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn default_key_mod() {
+        assert!(KeyMod::default().is_any());
+        assert!(KeyMod::default().matches(KeyMod::CTRL));
+    }
+
+    #[test]
+    fn display_key() {
+        assert_eq!(Key(KeyMod::NONE, KeyCode::Char('a')).to_string(), "a");
+        assert_eq!(Key(KeyMod::ANY, KeyCode::Char('a')).to_string(), "any+a");
+        assert_eq!(Key(KeyMod::SHIFT | KeyMod::CTRL, KeyCode::Char('a')).to_string(), "ctrl+shift+a");
+        assert_eq!(Key(KeyMod::ALT, KeyCode::F(4)).to_string(), "alt+f4");
+        assert_eq!(Key(KeyMod::CTRL, KeyCode::Char('+')).to_string(), "ctrl+plus");
+        assert_eq!(Key(KeyMod::NONE, KeyCode::PageDown).to_string(), "page_down");
+        assert_eq!(Key(KeyMod::META, KeyCode::Mod(ModKeyCode::Shift)).to_string(), "meta+shift");
+    }
+
+    #[test]
+    fn key_round_trip() {
+        let codes = KeyCode::NAMED
+            .into_iter()
+            .map(|(c, _)| c)
+            .chain([KeyCode::Char('a'), KeyCode::Char('-'), KeyCode::Char(' '), KeyCode::Char('<'), KeyCode::F(12)]);
+        let mods = [KeyMod::NONE, KeyMod::ANY, KeyMod::CTRL, KeyMod::SHIFT_CTRL_ALT, KeyMod::SUPER | KeyMod::META];
+
+        for code in codes {
+            for m in mods {
+                let k = Key(m, code);
+                let parsed = key(&k.to_string());
+                assert_eq!((parsed.key_mod(), parsed.code()), (m, code), "{}", k);
+            }
+        }
+    }
+
+    #[test]
+    fn function_key_round_trip() {
+        // The decoder and crossterm report up to F35
+        for n in 1..=35 {
+            let code = KeyCode::F(n);
+            assert_eq!(code.to_string().parse(), Ok(code));
+            assert_eq!(key(&Key(KeyMod::CTRL, code).to_string()).code(), code);
+        }
+
+        assert_eq!("F255".parse(), Ok(KeyCode::F(KeyCode::MAX_F)));
+        assert!("f0".parse::<KeyCode>().is_err());
+        assert!("f256".parse::<KeyCode>().is_err());
+    }
+}
+
 #[cfg(feature="serde")]
 #[cfg(test)]
 mod serde_tests {
@@ -204,6 +632,10 @@ mod serde_tests {
     struct M {
         m: ModKeyCode
     }
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct KM {
+        m: KeyMod
+    }
 
     fn key(s: &str) -> Result<Key, toml::de::Error> {
         let tbl = toml::from_str::<K>(s)?;
@@ -217,20 +649,54 @@ mod serde_tests {
         let tbl = toml::from_str::<M>(s)?;
         Ok(tbl.m)
     }
+    fn key_mod(s: &str) -> Result<KeyMod, toml::de::Error> {
+        let tbl = toml::from_str::<KM>(s)?;
+        Ok(tbl.m)
+    }
 
     #[test]
     fn des_key() {
-        assert_eq!(key(r#"k = ["", "none"]"#).unwrap(), Key(KeyMod::None, KeyCode::None));
-        assert_eq!(key(r#"k = ["", "a"]"#).unwrap(), Key(KeyMod::None, KeyCode::Char('a')));
-        assert_eq!(key(r#"k = ["", "f2"]"#).unwrap(), Key(KeyMod::None, KeyCode::F(2)));
-        assert_eq!(key(r#"k = ["shift", "b"]"#).unwrap(), Key(KeyMod::Shift, KeyCode::Char('b')));
-        assert_eq!(key(r#"k = ["shift_ctrl", "shift"]"#).unwrap(), Key(KeyMod::ShiftCtrl, KeyCode::Mod(ModKeyCode::Shift)));
-
-        assert!(key(r#"k = "a""#).is_err());
-        assert!(key(r#"k = "shift""#).is_err());
+        assert_eq!(key(r#"k = ["", "none"]"#).unwrap(), Key(KeyMod::NONE, KeyCode::None));
+        assert_eq!(key(r#"k = ["", "a"]"#).unwrap(), Key(KeyMod::NONE, KeyCode::Char('a')));
+        assert_eq!(key(r#"k = ["", "f2"]"#).unwrap(), Key(KeyMod::NONE, KeyCode::F(2)));
+        assert_eq!(key(r#"k = ["shift", "b"]"#).unwrap(), Key(KeyMod::SHIFT, KeyCode::Char('b')));
+        assert_eq!(key(r#"k = ["shift_ctrl", "shift"]"#).unwrap(), Key(KeyMod::SHIFT_CTRL, KeyCode::Mod(ModKeyCode::Shift)));
+        assert_eq!(key(r#"k = ["ctrl_super", "k"]"#).unwrap().key_mod(), KeyMod::CTRL | KeyMod::SUPER);
+        assert_ne!(key(r#"k = ["ctrl_super", "k"]"#).unwrap(), Key(KeyMod::NONE, KeyCode::Char('k')));
+
+        assert_eq!(key(r#"k = "a""#).unwrap(), Key(KeyMod::NONE, KeyCode::Char('a')));
+        assert_eq!(key(r#"k = "shift""#).unwrap(), Key(KeyMod::NONE, KeyCode::Mod(ModKeyCode::Shift)));
+        assert_eq!(key(r#"k = "ctrl+shift+a""#).unwrap(), Key(KeyMod::CTRL | KeyMod::SHIFT, KeyCode::Char('a')));
+        assert_eq!(key(r#"k = "<C-x>""#).unwrap(), Key(KeyMod::CTRL, KeyCode::Char('x')));
+
+        assert!(key(r#"k = "foo+a""#).is_err());
+        assert!(key(r#"k = "ctrl+""#).is_err());
         assert!(key(r#"k = []"#).is_err());
         assert!(key(r#"k = ["foo", "a"]"#).is_err());
         assert!(key(r#"k = ["bar"]"#).is_err());
+        assert!(key(r#"k = ["ctrl", "a", "b"]"#).is_err());
+    }
+
+    #[test]
+    fn ser_key() {
+        let ser = |k: Key| toml::to_string(&K { k }).unwrap();
+
+        for k in [
+            Key(KeyMod::NONE, KeyCode::Char('a')),
+            Key(KeyMod::ANY, KeyCode::Char('a')),
+            Key(KeyMod::CTRL | KeyMod::SHIFT, KeyCode::Char('+')),
+            Key(KeyMod::ALT, KeyCode::F(4)),
+            Key(KeyMod::SUPER, KeyCode::PageUp),
+            Key(KeyMod::NONE, KeyCode::Char(' ')),
+        ] {
+            let s = ser(k);
+            let des = key(&s).unwrap();
+            assert_eq!(des.key_mod(), k.key_mod(), "{}", s);
+            assert_eq!(des.code(), k.code(), "{}", s);
+        }
+
+        assert_eq!(ser(Key(KeyMod::CTRL, KeyCode::Char('a'))).trim(), r#"k = "ctrl+a""#);
+        assert_eq!(toml::to_string(&C { c: KeyCode::F(1) }).unwrap().trim(), r#"c = "f1""#);
     }
 
     #[test]
@@ -246,7 +712,9 @@ mod serde_tests {
 
         assert!(key_code(r#"c = "ab""#).is_err());
         assert!(key_code(r#"c = 10"#).is_err());
-        assert!(key_code(r#"c = "f13""#).is_err());
+        assert_eq!(key_code(r#"c = "f13""#).unwrap(), KeyCode::F(13));
+        assert!(key_code(r#"c = "f0""#).is_err());
+        assert!(key_code(r#"c = "f256""#).is_err());
         assert!(key_code(r#"c = "shift_ctrl""#).is_err());
     }
     
@@ -261,4 +729,27 @@ mod serde_tests {
         assert!(mod_key_code(r#"m = 1"#).is_err());
         assert!(mod_key_code(r#"m = "foo""#).is_err());
     }
+
+    #[test]
+    fn des_key_mod() {
+        assert_eq!(key_mod(r#"m = """#).unwrap(), KeyMod::ANY);
+        assert_eq!(key_mod(r#"m = "any""#).unwrap(), KeyMod::ANY);
+        assert_eq!(key_mod(r#"m = "none""#).unwrap(), KeyMod::NONE);
+        assert_eq!(key_mod(r#"m = "ctrl_alt""#).unwrap(), KeyMod::CTRL_ALT);
+        assert_eq!(key_mod(r#"m = "alt_ctrl""#).unwrap(), KeyMod::CTRL_ALT);
+        assert_eq!(key_mod(r#"m = "super_hyper_meta""#).unwrap(), KeyMod::SUPER | KeyMod::HYPER | KeyMod::META);
+
+        assert!(key_mod(r#"m = "foo""#).is_err());
+        assert!(key_mod(r#"m = "ctrl_""#).is_err());
+        assert!(key_mod(r#"m = 1"#).is_err());
+    }
+
+    #[test]
+    fn ser_key_mod() {
+        let ser = |m: KeyMod| toml::to_string(&KM { m }).unwrap();
+
+        assert_eq!(ser(KeyMod::ANY).trim(), r#"m = "any""#);
+        assert_eq!(ser(KeyMod::NONE).trim(), r#"m = "none""#);
+        assert_eq!(ser(KeyMod::CTRL | KeyMod::SHIFT | KeyMod::META).trim(), r#"m = "ctrl+shift+meta""#);
+    }
 }
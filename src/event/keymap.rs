@@ -0,0 +1,772 @@
+use std::{collections::HashMap, fmt::{self, Display}, str::FromStr, time::{Duration, Instant}};
+
+use super::{Key, ParseKeyError};
+
+/// Key sequence
+/// One or more keys that must be pressed one after another, for example `g g` or `ctrl+x ctrl+s`
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::event::*;
+/// let seq: KeySeq = "ctrl+x ctrl+s".parse().unwrap();
+///
+/// assert_eq!(seq.keys(), &[
+///     Key(KeyMod::CTRL, KeyCode::Char('x')),
+///     Key(KeyMod::CTRL, KeyCode::Char('s')),
+/// ]);
+/// assert_eq!(seq.to_string(), "ctrl+x ctrl+s");
+/// ```
+///
+/// # Notes
+///
+/// - unlike [Key], sequences are compared structurally, so `any+q` is not equal to `q`,
+///   use [KeySeq::is] to check whether a sequence matches pressed keys
+#[derive(Debug, Default, Clone, Eq)]
+pub struct KeySeq(pub Vec<Key>);
+impl KeySeq {
+    pub fn new<K: Into<Vec<Key>>>(keys: K) -> Self {
+        Self(keys.into())
+    }
+
+    //
+
+    /// Returns `true` if this sequence starts with `keys`
+    pub fn starts_with(&self, keys: &[Key]) -> bool {
+        self.0.len() >= keys.len() && self.matches(keys)
+    }
+    /// Returns `true` if every key of `keys` matches the key of this sequence in the same position
+    /// Keys are compared using [Key::normalized]
+    fn matches(&self, keys: &[Key]) -> bool {
+        self.0
+            .iter()
+            .zip(keys)
+            .all(|(a, b)| a.normalized() == b.normalized())
+    }
+    /// Returns `true` if this sequence is the same as `keys`
+    pub fn is(&self, keys: &[Key]) -> bool {
+        self.0.len() == keys.len() && self.matches(keys)
+    }
+    /// Returns `true` if both sequences consist of the same keys
+    /// Modifiers are compared by their bits, so [KeyMod::ANY] is only the same as itself
+    ///
+    /// [KeyMod::ANY]: super::KeyMod::ANY
+    pub fn is_same(&self, other: &KeySeq) -> bool {
+        self.0.len() == other.0.len()
+            && self.0
+                .iter()
+                .zip(&other.0)
+                .map(|(a, b)| (a.normalized(), b.normalized()))
+                .all(|(a, b)| a.key_mod().bits() == b.key_mod().bits() && a.code() == b.code())
+    }
+
+    pub fn keys(&self) -> &[Key] {
+        &self.0
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+impl PartialEq for KeySeq {
+    /// See [KeySeq::is_same]
+    fn eq(&self, other: &Self) -> bool {
+        self.is_same(other)
+    }
+}
+impl Display for KeySeq {
+    /// Writes keys separated with a space
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, key) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", key)?;
+        }
+
+        Ok(())
+    }
+}
+impl FromStr for KeySeq {
+    type Err = ParseKeyError;
+
+    /// Parses keys separated with whitespace (see [Key::from_str])
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(Key::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if keys.is_empty() {
+            return Err(ParseKeyError::new("expected at least one key"));
+        }
+
+        Ok(Self(keys))
+    }
+}
+impl From<Key> for KeySeq {
+    fn from(value: Key) -> Self {
+        Self(vec![value])
+    }
+}
+impl From<Vec<Key>> for KeySeq {
+    fn from(value: Vec<Key>) -> Self {
+        Self(value)
+    }
+}
+impl<const N: usize> From<[Key; N]> for KeySeq {
+    fn from(value: [Key; N]) -> Self {
+        Self(value.into())
+    }
+}
+
+#[cfg(feature="serde")]
+impl serde::Serialize for KeySeq {
+    /// Serializes using [Display], for example `ctrl+x ctrl+s`
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
+}
+#[cfg(feature="serde")]
+impl<'de> serde::Deserialize<'de> for KeySeq {
+    /// Deserializes using [FromStr]
+    fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+        des.deserialize_str(super::FromStrVisitor::<Self>::new("key sequence, for example \"g g\""))
+    }
+}
+
+/// Keymap binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding<A> {
+    pub keys: KeySeq,
+    pub action: A,
+}
+
+/// Keymap layer
+/// A list of bindings, the first matching binding wins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyLayer<A> {
+    pub bindings: Vec<Binding<A>>,
+}
+impl<A> KeyLayer<A> {
+    pub fn new() -> Self {
+        Self { bindings: vec![] }
+    }
+
+    //
+
+    /// Bind `keys` to `action`
+    /// Returns the previous action if `keys` were already bound
+    pub fn insert<K: Into<KeySeq>>(&mut self, keys: K, action: A) -> Option<A> {
+        let keys: KeySeq = keys.into();
+
+        if let Some(binding) = self.bindings.iter_mut().find(|b| b.keys.is_same(&keys)) {
+            return Some(std::mem::replace(&mut binding.action, action));
+        }
+
+        self.bindings.push(Binding { keys, action });
+        None
+    }
+    /// Remove the binding of `keys`
+    /// Returns its action if it existed
+    pub fn remove(&mut self, keys: &KeySeq) -> Option<A> {
+        let index = self.bindings.iter().position(|b| b.keys.is_same(keys))?;
+        Some(self.bindings.remove(index).action)
+    }
+    /// Returns the action bound exactly to `keys`
+    pub fn get(&self, keys: &[Key]) -> Option<&A> {
+        self.bindings
+            .iter()
+            .find(|b| b.keys.is(keys))
+            .map(|b| &b.action)
+    }
+    /// Returns `true` if there is a binding longer than `keys` that starts with `keys`
+    pub fn has_longer(&self, keys: &[Key]) -> bool {
+        self.bindings
+            .iter()
+            .any(|b| b.keys.len() > keys.len() && b.keys.starts_with(keys))
+    }
+
+    /// Returns every pair of conflicting bindings
+    /// See [KeyConflict]
+    pub fn conflicts(&self) -> Vec<KeyConflict> {
+        let mut conflicts = vec![];
+
+        for (index, a) in self.bindings.iter().enumerate() {
+            for b in &self.bindings[index + 1..] {
+                let (short, long) =
+                    if a.keys.len() <= b.keys.len() { (a, b) }
+                    else { (b, a) };
+
+                if !long.keys.starts_with(short.keys.keys()) {
+                    continue;
+                }
+
+                conflicts.push(if short.keys.len() == long.keys.len() {
+                    KeyConflict::Same(short.keys.clone(), long.keys.clone())
+                } else {
+                    KeyConflict::Prefix(short.keys.clone(), long.keys.clone())
+                });
+            }
+        }
+
+        conflicts
+    }
+}
+impl<A> Default for KeyLayer<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keymap conflict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyConflict {
+    /// Both sequences match the same keys (for example `ctrl+a` and `any+a`)
+    /// Only the first one will ever be triggered
+    Same(KeySeq, KeySeq),
+    /// The first sequence is the start of the second one (for example `g` and `g g`)
+    /// The first one will only be triggered after the timeout
+    Prefix(KeySeq, KeySeq),
+}
+
+/// Result of [Keymap::handle]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapResult<A> {
+    /// A binding was triggered
+    Action(A),
+    /// The key is a part of a longer sequence, waiting for more keys
+    Pending,
+    /// No binding matches the key
+    Unbound,
+    /// Pending keys were resolved to a binding of their start (for example `g` when `g g` is also bound),
+    /// because the sequence was broken or timed out
+    /// The keys after it were handled again, their result is the second field
+    Resolved(A, Box<KeymapResult<A>>),
+}
+impl<A> KeymapResult<A> {
+    /// Returns the first triggered action
+    pub fn action(self) -> Option<A> {
+        match self {
+            Self::Action(action) | Self::Resolved(action, _) => Some(action),
+            _ => None
+        }
+    }
+    /// Returns all triggered actions in order
+    pub fn actions(self) -> Vec<A> {
+        match self {
+            Self::Action(action) => vec![action],
+            Self::Resolved(action, next) => {
+                let mut actions = vec![action];
+                actions.extend(next.actions());
+                actions
+            },
+            _ => vec![]
+        }
+    }
+
+    /// Chain the result of keys handled after this one
+    fn then(self, next: Self) -> Self {
+        match self {
+            Self::Action(action) => Self::Resolved(action, Box::new(next)),
+            Self::Resolved(action, rest) => Self::Resolved(action, Box::new(rest.then(next))),
+            _ => next
+        }
+    }
+}
+
+/// Keymap
+/// Maps key sequences to user actions
+///
+/// Bindings of the current mode are checked before the global ones.
+/// When the pressed keys are a start of a longer sequence, the keymap waits for more keys
+/// until [Keymap::timeout] passes (see [Keymap::check_timeout])
+/// or another key breaks the sequence, then the binding of its start is triggered (see [KeymapResult::Resolved])
+///
+/// # Serde
+///
+/// Can be loaded from a config file:
+///
+/// ```toml
+/// [keys]
+/// "ctrl+x ctrl+s" = "save"
+/// "g g" = "top"
+///
+/// [modes.insert]
+/// "esc" = "normal_mode"
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::event::*;
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Action { Top, Save, Quit }
+///
+/// let mut keymap = Keymap::new()
+///     .bind("g g".parse::<KeySeq>().unwrap(), Action::Top)
+///     .bind("ctrl+x ctrl+s".parse::<KeySeq>().unwrap(), Action::Save)
+///     .bind(Key(KeyMod::NONE, KeyCode::Char('q')), Action::Quit);
+///
+/// assert_eq!(keymap.handle(Key(KeyMod::NONE, KeyCode::Char('g'))), KeymapResult::Pending);
+/// assert_eq!(keymap.handle(Key(KeyMod::NONE, KeyCode::Char('g'))), KeymapResult::Action(Action::Top));
+/// assert_eq!(keymap.handle(Key(KeyMod::NONE, KeyCode::Char('q'))), KeymapResult::Action(Action::Quit));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    /// Bindings that are active in every mode
+    pub global: KeyLayer<A>,
+    /// Bindings that are active only in a certain mode
+    pub modes: HashMap<String, KeyLayer<A>>,
+    /// How long to wait for the next key of a sequence
+    /// `None` to wait forever
+    pub timeout: Option<Duration>,
+
+    mode: Option<String>,
+    pending: Vec<Key>,
+    last_key_time: Option<Instant>,
+}
+impl<A> Keymap<A> {
+    /// Default [Keymap::timeout]
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    pub fn new() -> Self {
+        Self {
+            global: KeyLayer::new(),
+            modes: HashMap::new(),
+            timeout: Some(Self::DEFAULT_TIMEOUT),
+            mode: None,
+            pending: vec![],
+            last_key_time: None,
+        }
+    }
+
+    //
+
+    /// Bind `keys` to `action` in every mode
+    pub fn bind<K: Into<KeySeq>>(mut self, keys: K, action: A) -> Self {
+        self.global.insert(keys, action);
+        self
+    }
+    /// Bind `keys` to `action` only in `mode`
+    pub fn bind_mode<M, K>(mut self, mode: M, keys: K, action: A) -> Self
+    where M: ToString,
+          K: Into<KeySeq>
+    {
+        self.layer_mut(mode).insert(keys, action);
+        self
+    }
+    /// Set how long to wait for the next key of a sequence
+    pub fn timeout<T: Into<Option<Duration>>>(mut self, timeout: T) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    /// Get a layer of `mode`, create it if doesn't exist
+    pub fn layer_mut<M: ToString>(&mut self, mode: M) -> &mut KeyLayer<A> {
+        self.modes.entry(mode.to_string()).or_default()
+    }
+    /// Set current mode
+    /// `None` to use only the global bindings
+    /// Resets pending keys
+    pub fn set_mode<M: ToString>(&mut self, mode: Option<M>) {
+        self.mode = mode.map(|m| m.to_string());
+        self.reset();
+    }
+    /// Current mode
+    pub fn mode(&self) -> Option<&str> {
+        self.mode.as_deref()
+    }
+
+    /// Forget pending keys
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.last_key_time = None;
+    }
+    /// Keys pressed so far of a not yet completed sequence
+    pub fn pending(&self) -> &[Key] {
+        &self.pending
+    }
+
+    /// Returns every pair of conflicting bindings in the global layer
+    /// and in each mode layer combined with the global one
+    pub fn conflicts(&self) -> Vec<KeyConflict> where A: Clone {
+        let mut conflicts = self.global.conflicts();
+
+        for layer in self.modes.values() {
+            let mut combined = layer.clone();
+            combined.bindings.extend(self.global.bindings.iter().cloned());
+
+            for conflict in combined.conflicts() {
+                if !conflicts.contains(&conflict) {
+                    conflicts.push(conflict);
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    fn layers(&self) -> impl Iterator<Item = &KeyLayer<A>> {
+        self.mode
+            .as_ref()
+            .and_then(|m| self.modes.get(m))
+            .into_iter()
+            .chain(std::iter::once(&self.global))
+    }
+    fn get(&self, keys: &[Key]) -> Option<&A> {
+        self.layers().find_map(|l| l.get(keys))
+    }
+    fn has_longer(&self, keys: &[Key]) -> bool {
+        self.layers().any(|l| l.has_longer(keys))
+    }
+    fn is_timed_out(&self, now: Instant) -> bool {
+        match (self.timeout, self.last_key_time) {
+            (Some(timeout), Some(time)) => now.saturating_duration_since(time) >= timeout,
+            _ => false
+        }
+    }
+}
+impl<A: Clone> Keymap<A> {
+    /// Handle a pressed key
+    pub fn handle(&mut self, key: Key) -> KeymapResult<A> {
+        self.handle_at(key, Instant::now())
+    }
+    /// Same as [Keymap::handle], but with a custom current time
+    pub fn handle_at(&mut self, key: Key, now: Instant) -> KeymapResult<A> {
+        if let Some(action) = self.check_timeout_at(now) {
+            return KeymapResult::Resolved(action, Box::new(self.handle_at(key, now)));
+        }
+
+        self.pending.push(key);
+        self.last_key_time = Some(now);
+
+        if self.has_longer(&self.pending) {
+            return KeymapResult::Pending;
+        }
+        if let Some(action) = self.get(&self.pending).cloned() {
+            self.reset();
+            return KeymapResult::Action(action);
+        }
+
+        // The sequence is broken, trigger the longest bound start of it and handle the keys after it again
+        let keys = std::mem::take(&mut self.pending);
+        self.reset();
+
+        let resolved = (1..keys.len())
+            .rev()
+            .find_map(|len| self.get(&keys[..len]).cloned().map(|action| (len, action)));
+
+        match resolved {
+            Some((len, action)) => KeymapResult::Resolved(action, Box::new(self.replay(&keys[len..], now))),
+            // Try the last key on its own
+            None if keys.len() > 1 => self.handle_at(key, now),
+            None => KeymapResult::Unbound
+        }
+    }
+    /// Handle `keys` one after another, keeping every triggered action
+    fn replay(&mut self, keys: &[Key], now: Instant) -> KeymapResult<A> {
+        let mut result: Option<KeymapResult<A>> = None;
+
+        for key in keys {
+            let next = self.handle_at(*key, now);
+            result = Some(match result {
+                Some(result) => result.then(next),
+                None => next
+            });
+        }
+
+        result.unwrap_or(KeymapResult::Pending)
+    }
+    /// Check whether the pending keys have timed out
+    /// Returns the action bound exactly to the pending keys, if any (for example `g` when `g g` is also bound)
+    ///
+    /// Call it periodically, for example when [BackendEventReader::read_timeout] returns `None`
+    ///
+    /// [BackendEventReader::read_timeout]: crate::backend::BackendEventReader::read_timeout
+    pub fn check_timeout(&mut self) -> Option<A> {
+        self.check_timeout_at(Instant::now())
+    }
+    /// Same as [Keymap::check_timeout], but with a custom current time
+    pub fn check_timeout_at(&mut self, now: Instant) -> Option<A> {
+        if self.pending.is_empty() || !self.is_timed_out(now) {
+            return None;
+        }
+
+        let action = self.get(&self.pending).cloned();
+        self.reset();
+        action
+    }
+}
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature="serde")]
+impl<A: serde::Serialize> serde::Serialize for KeyLayer<A> {
+    /// Serializes into a map of key sequences to actions
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = ser.serialize_map(Some(self.bindings.len()))?;
+        for binding in &self.bindings {
+            map.serialize_entry(&binding.keys.to_string(), &binding.action)?;
+        }
+        map.end()
+    }
+}
+#[cfg(feature="serde")]
+impl<'de, A: serde::Deserialize<'de>> serde::Deserialize<'de> for KeyLayer<A> {
+    /// Deserializes from a map of key sequences to actions, for example `{ "g g" = "top" }`
+    fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+        des.deserialize_map(KeyLayerVisitor(std::marker::PhantomData))
+    }
+}
+
+#[cfg(feature="serde")]
+struct KeyLayerVisitor<A>(std::marker::PhantomData<A>);
+#[cfg(feature="serde")]
+impl<'de, A: serde::Deserialize<'de>> serde::de::Visitor<'de> for KeyLayerVisitor<A> {
+    type Value = KeyLayer<A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "map of key sequences to actions")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where M: serde::de::MapAccess<'de>
+    {
+        use serde::de::Error;
+
+        let mut layer = KeyLayer::new();
+        while let Some((keys, action)) = map.next_entry::<String, A>()? {
+            let keys: KeySeq = keys.parse().map_err(M::Error::custom)?;
+            layer.insert(keys, action);
+        }
+
+        Ok(layer)
+    }
+}
+
+#[cfg(feature="serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(serialize="A: serde::Serialize", deserialize="A: serde::Deserialize<'de>"))]
+struct KeymapData<A> {
+    #[serde(default)]
+    keys: KeyLayer<A>,
+    #[serde(default)]
+    modes: HashMap<String, KeyLayer<A>>,
+}
+
+#[cfg(feature="serde")]
+impl<A: Clone + serde::Serialize> serde::Serialize for Keymap<A> {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        KeymapData {
+            keys: self.global.clone(),
+            modes: self.modes.clone(),
+        }.serialize(ser)
+    }
+}
+#[cfg(feature="serde")]
+impl<'de, A: serde::Deserialize<'de>> serde::Deserialize<'de> for Keymap<A> {
+    /// Deserializes from `keys` (global bindings) and `modes` (bindings of each mode) tables
+    fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+        let data = KeymapData::deserialize(des)?;
+
+        let mut keymap = Self::new();
+        keymap.global = data.keys;
+        keymap.modes = data.modes;
+        Ok(keymap)
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use crate::event::{KeyCode, KeyMod};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature="serde", derive(serde::Deserialize), serde(rename_all="snake_case"))]
+    enum Action {
+        Top,
+        Go,
+        Save,
+        Quit,
+        Leave,
+    }
+
+    fn seq(s: &str) -> KeySeq {
+        s.parse().unwrap()
+    }
+    fn key(s: &str) -> Key {
+        s.parse().unwrap()
+    }
+
+    fn keymap() -> Keymap<Action> {
+        Keymap::new()
+            .bind(seq("g g"), Action::Top)
+            .bind(seq("g"), Action::Go)
+            .bind(seq("ctrl+x ctrl+s"), Action::Save)
+            .bind(seq("q"), Action::Quit)
+            .bind_mode("insert", seq("esc"), Action::Leave)
+            .bind_mode("insert", seq("q"), Action::Leave)
+    }
+
+    #[test]
+    fn sequences() {
+        let mut km = keymap();
+
+        assert_eq!(km.handle(key("ctrl+x")), KeymapResult::Pending);
+        assert_eq!(km.pending(), &[key("ctrl+x")]);
+        assert_eq!(km.handle(key("ctrl+s")), KeymapResult::Action(Action::Save));
+        assert!(km.pending().is_empty());
+
+        assert_eq!(km.handle(key("g")), KeymapResult::Pending);
+        assert_eq!(km.handle(key("g")), KeymapResult::Action(Action::Top));
+
+        assert_eq!(km.handle(key("x")), KeymapResult::Unbound);
+        // Broken sequence retries the last key
+        assert_eq!(km.handle(key("ctrl+x")), KeymapResult::Pending);
+        assert_eq!(km.handle(key("q")), KeymapResult::Action(Action::Quit));
+    }
+
+    #[test]
+    fn timeout() {
+        let mut km = keymap().timeout(Duration::from_millis(100));
+        let now = Instant::now();
+
+        assert_eq!(km.handle_at(key("g"), now), KeymapResult::Pending);
+        assert_eq!(km.check_timeout_at(now + Duration::from_millis(50)), None);
+        assert_eq!(km.check_timeout_at(now + Duration::from_millis(100)), Some(Action::Go));
+        assert!(km.pending().is_empty());
+
+        assert_eq!(km.handle_at(key("ctrl+x"), now), KeymapResult::Pending);
+        assert_eq!(km.handle_at(key("ctrl+s"), now + Duration::from_secs(1)), KeymapResult::Unbound);
+
+        // A timed out prefix is resolved by the next key, even without checking the timeout
+        assert_eq!(km.handle_at(key("g"), now), KeymapResult::Pending);
+        assert_eq!(
+            km.handle_at(key("q"), now + Duration::from_secs(1)),
+            KeymapResult::Resolved(Action::Go, Box::new(KeymapResult::Action(Action::Quit)))
+        );
+    }
+
+    #[test]
+    fn broken_prefix() {
+        let mut km = keymap().timeout(None);
+
+        // `g` is bound on its own, so it's triggered before the next key is handled
+        assert_eq!(km.handle(key("g")), KeymapResult::Pending);
+        assert_eq!(km.handle(key("q")).actions(), [Action::Go, Action::Quit]);
+        assert!(km.pending().is_empty());
+
+        assert_eq!(km.handle(key("g")), KeymapResult::Pending);
+        assert_eq!(
+            km.handle(key("x")),
+            KeymapResult::Resolved(Action::Go, Box::new(KeymapResult::Unbound))
+        );
+
+        assert_eq!(km.handle(key("g")), KeymapResult::Pending);
+        let result = km.handle(key("ctrl+x"));
+        assert_eq!(result.action(), Some(Action::Go));
+        assert_eq!(km.pending(), &[key("ctrl+x")], "The next key starts a new sequence");
+        assert_eq!(km.handle(key("ctrl+s")), KeymapResult::Action(Action::Save));
+
+        // The longest bound start wins
+        let mut km = Keymap::new()
+            .bind(seq("a"), Action::Go)
+            .bind(seq("a b"), Action::Top)
+            .bind(seq("a b c"), Action::Save)
+            .bind(seq("q"), Action::Quit)
+            .timeout(None);
+        km.handle(key("a"));
+        km.handle(key("b"));
+        assert_eq!(km.handle(key("q")).actions(), [Action::Top, Action::Quit]);
+    }
+
+    #[test]
+    fn modes() {
+        let mut km = keymap();
+
+        assert_eq!(km.handle(key("esc")), KeymapResult::Unbound);
+
+        km.set_mode(Some("insert"));
+        assert_eq!(km.mode(), Some("insert"));
+        assert_eq!(km.handle(key("esc")), KeymapResult::Action(Action::Leave));
+        assert_eq!(km.handle(key("q")), KeymapResult::Action(Action::Leave), "Mode bindings go first");
+        assert_eq!(km.handle(key("g")), KeymapResult::Pending, "Global bindings are still active");
+
+        km.set_mode::<&str>(None);
+        assert_eq!(km.handle(key("q")), KeymapResult::Action(Action::Quit));
+    }
+
+    #[test]
+    fn shifted_chars() {
+        let mut km = Keymap::new()
+            .bind(seq("G"), Action::Top);
+
+        assert_eq!(km.handle(Key(KeyMod::SHIFT, KeyCode::Char('G'))), KeymapResult::Action(Action::Top));
+        assert_eq!(km.handle(Key(KeyMod::NONE, KeyCode::Char('G'))), KeymapResult::Action(Action::Top));
+        assert_eq!(km.handle(Key(KeyMod::NONE, KeyCode::Char('g'))), KeymapResult::Unbound);
+
+        // Chars without case keep shift
+        let mut km = Keymap::new()
+            .bind(seq("shift+1"), Action::Top)
+            .bind(seq("1"), Action::Quit);
+
+        assert!(km.conflicts().is_empty());
+        assert_ne!(seq("shift+1"), seq("1"));
+        assert_eq!(km.handle(key("shift+1")), KeymapResult::Action(Action::Top));
+        assert_eq!(km.handle(key("1")), KeymapResult::Action(Action::Quit));
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut km = keymap();
+
+        // `any+q` matches `q`, but it's a different binding
+        assert_eq!(km.global.insert(seq("any+q"), Action::Leave), None);
+        assert_eq!(km.global.get(&[key("q")]), Some(&Action::Quit));
+        assert_eq!(km.global.conflicts(), [KeyConflict::Prefix(seq("g"), seq("g g")), KeyConflict::Same(seq("q"), seq("any+q"))]);
+        assert_ne!(seq("q"), seq("any+q"));
+        assert_eq!(seq("shift+g"), seq("G"));
+
+        let conflicts = km.conflicts();
+
+        assert!(conflicts.contains(&KeyConflict::Prefix(seq("g"), seq("g g"))));
+        assert!(conflicts.contains(&KeyConflict::Same(seq("q"), seq("any+q"))));
+        assert!(conflicts.contains(&KeyConflict::Same(seq("q"), seq("q"))), "Mode binding shadows a global one");
+        assert!(!conflicts.iter().any(|c| matches!(c, KeyConflict::Same(a, _) if a == &seq("esc"))));
+
+        assert_eq!(km.global.remove(&seq("any+q")), Some(Action::Leave));
+        assert_eq!(km.global.get(&[key("q")]), Some(&Action::Quit));
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn des_keymap() {
+        let mut km: Keymap<Action> = toml::from_str(r#"
+            [keys]
+            "ctrl+x ctrl+s" = "save"
+            "g g" = "top"
+
+            [modes.insert]
+            "<Esc>" = "leave"
+        "#).unwrap();
+
+        assert_eq!(km.handle(key("g")), KeymapResult::Pending);
+        assert_eq!(km.handle(key("g")), KeymapResult::Action(Action::Top));
+
+        km.set_mode(Some("insert"));
+        assert_eq!(km.handle(key("esc")), KeymapResult::Action(Action::Leave));
+
+        assert!(toml::from_str::<Keymap<Action>>(r#"keys = { "foo+a" = "save" }"#).is_err());
+    }
+}
@@ -1,7 +1,19 @@
+mod decoder;
+mod focus;
+mod gesture;
+mod hit;
 mod key;
+mod keymap;
 mod mouse;
 
+use std::{fmt::Display, str::FromStr};
+
+pub use decoder::*;
+pub use focus::*;
+pub use gesture::*;
+pub use hit::*;
 pub use key::*;
+pub use keymap::*;
 pub use mouse::*;
 
 #[allow(unused)]
@@ -11,7 +23,13 @@ use crate::backend::crossterm::CrosstermBackend;
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
 pub enum Event {
-    Key(Key, KeyCode),
+    /// Key event with the key, its code and additional info
+    ///
+    /// # Notes
+    ///
+    /// - some terminals report key releases as well (see [KeyInfo::kind]),
+    ///   use [Event::key_press] or [KeyInfo::is_press] to ignore them
+    Key(Key, KeyCode, KeyInfo),
     Mouse(Mouse, u16, u16),
     Paste(String),
     Resize(u16, u16),
@@ -21,75 +39,317 @@ pub enum Event {
     Blur,
     Unknown
 }
+impl Event {
+    /// Returns the key if this is a key event
+    pub fn key(&self) -> Option<Key> {
+        match self {
+            Self::Key(key, _, _) => Some(*key),
+            _ => None
+        }
+    }
+    /// Returns the key if this is a key press or repeat event
+    /// Useful to ignore key releases, which are reported by some terminals
+    pub fn key_press(&self) -> Option<Key> {
+        match self {
+            Self::Key(key, _, info) if info.is_press() => Some(*key),
+            _ => None
+        }
+    }
+    /// Returns `true` if this is a key release event
+    pub fn is_key_release(&self) -> bool {
+        matches!(self, Self::Key(_, _, info) if info.is_release())
+    }
+}
 
 /// Key modifier
-#[derive(Debug, Default, Clone, Copy, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
-pub enum KeyMod {
-    #[default]
-    #[cfg_attr(feature="serde", serde(alias=""))]
-    Any,
-    None,
-    Shift,
-    Ctrl,
-    Alt,
-    ShiftCtrl,
-    ShiftAlt,
-    ShiftCtrlAlt,
-    CtrlAlt
-}
+/// A set of modifier keys that can be combined with `|`
+///
+/// [KeyMod::ANY] is a wildcard, keys with it will match keys with any modifiers (see [KeyMod::matches])
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::event::*;
+/// let mods = KeyMod::CTRL | KeyMod::SUPER;
+///
+/// assert!(mods.ctrl());
+/// assert!(mods.contains(KeyMod::SUPER));
+/// assert!(!mods.contains(KeyMod::SHIFT));
+/// assert!(KeyMod::ANY.matches(mods));
+/// assert!(!KeyMod::CTRL.matches(mods));
+/// assert!(KeyMod::default().is_any());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyMod(u8);
 impl KeyMod {
+    /// Matches any modifiers
+    pub const ANY: Self = Self(Self::ANY_BIT);
+    /// No modifiers
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(1 << 0);
+    pub const CTRL: Self = Self(1 << 1);
+    pub const ALT: Self = Self(1 << 2);
+    /// Win/super key
+    pub const SUPER: Self = Self(1 << 3);
+    pub const HYPER: Self = Self(1 << 4);
+    pub const META: Self = Self(1 << 5);
+
+    pub const SHIFT_CTRL: Self = Self(Self::SHIFT.0 | Self::CTRL.0);
+    pub const SHIFT_ALT: Self = Self(Self::SHIFT.0 | Self::ALT.0);
+    pub const CTRL_ALT: Self = Self(Self::CTRL.0 | Self::ALT.0);
+    pub const SHIFT_CTRL_ALT: Self = Self(Self::SHIFT.0 | Self::CTRL.0 | Self::ALT.0);
+
+    /// Every modifier with its name in the order they are displayed
+    pub const NAMED: [(Self, &'static str); 6] = [
+        (Self::CTRL, "ctrl"),
+        (Self::SHIFT, "shift"),
+        (Self::ALT, "alt"),
+        (Self::SUPER, "super"),
+        (Self::HYPER, "hyper"),
+        (Self::META, "meta"),
+    ];
+
+    const ANY_BIT: u8 = 1 << 7;
+    const ALL_BITS: u8 = (1 << 6) - 1;
+
+    /// Creates a [KeyMod] from raw bits
+    /// Unknown bits are ignored
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits & (Self::ALL_BITS | Self::ANY_BIT))
+    }
+
+    //
+
+    /// Returns `true` if `self` and `other` are equal or one of them is [KeyMod::ANY]
+    pub fn matches(self, other: Self) -> bool {
+        self.is_any() || other.is_any() || self == other
+    }
+    /// Returns `true` if all of the modifiers in `other` are set
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    /// Returns `true` if any of the modifiers in `other` are set
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 & Self::ALL_BITS != 0
+    }
+    /// Set modifiers from `other`
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+    /// Unset modifiers from `other`
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+    /// Set or unset modifiers from `other`
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.insert(other)
+        } else {
+            self.remove(other)
+        }
+    }
+
+    /// Returns `true` if this is the [KeyMod::ANY] wildcard
+    pub fn is_any(self) -> bool {
+        self.0 & Self::ANY_BIT != 0
+    }
+    /// Returns `true` if no modifiers are set
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    pub fn shift(self) -> bool { self.contains(Self::SHIFT) }
+    pub fn ctrl(self) -> bool { self.contains(Self::CTRL) }
+    pub fn alt(self) -> bool { self.contains(Self::ALT) }
+    pub fn super_key(self) -> bool { self.contains(Self::SUPER) }
+    pub fn hyper(self) -> bool { self.contains(Self::HYPER) }
+    pub fn meta(self) -> bool { self.contains(Self::META) }
+
+    /// Returns raw bits
+    pub fn bits(self) -> u8 {
+        self.0
+    }
     /// Returns tuple of booleans `(shift, ctrl, alt)`
     pub fn tuple(&self) -> (bool, bool, bool) {
-        match self {
-            Self::Shift        => (true,  false, false),
-            Self::Ctrl         => (false, true,  false),
-            Self::Alt          => (false, false, true),
-            Self::ShiftCtrl    => (true,  true,  false),
-            Self::ShiftAlt     => (true,  false, true),
-            Self::ShiftCtrlAlt => (true,  true,  true),
-            Self::CtrlAlt      => (false, true,  true),
-            Self::None         => (false, false, false),
-            Self::Any          => (false, false, false),
+        if self.is_any() {
+            return (false, false, false);
         }
+
+        (self.shift(), self.ctrl(), self.alt())
     }
-}
-impl PartialEq for KeyMod {
-    /// This method tests for `self` and `other` values to be equal, and is used
-    /// Will always return `true` if `self` or `other` are equal to [KeyMod::Any]
-    fn eq(&self, other: &Self) -> bool {
-        match other {
-            Self::Any => true,
-            _ => match self {
-                Self::Any          => true,
-                Self::Shift        => match other { Self::Shift => true, _ => false },
-                Self::Ctrl         => match other { Self::Ctrl => true, _ => false },
-                Self::Alt          => match other { Self::Alt => true, _ => false },
-                Self::ShiftCtrl    => match other { Self::ShiftCtrl => true, _ => false },
-                Self::ShiftAlt     => match other { Self::ShiftAlt => true, _ => false },
-                Self::ShiftCtrlAlt => match other { Self::ShiftCtrlAlt => true, _ => false },
-                Self::CtrlAlt      => match other { Self::CtrlAlt => true, _ => false },
-                Self::None         => match other { Self::None => true, _ => false },
+    /// Returns an iterator over the names of the set modifiers
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::NAMED
+            .into_iter()
+            .filter(move |(m, _)| !self.is_any() && self.contains(*m))
+            .map(|(_, n)| n)
+    }
+    /// Returns a modifier by its name
+    ///
+    /// - full names are case-insensitive: `ctrl` (`control`), `shift`, `alt` (`option`),
+    ///   `super` (`win`, `cmd`), `hyper` and `meta`
+    /// - emacs/vim style single letters: `C` - ctrl, `S` - shift, `A`/`M` - alt,
+    ///   `s`/`D` - super, `H` - hyper
+    pub fn from_name(name: &str) -> Option<Self> {
+        let m = match name {
+            "C" | "c" => Self::CTRL,
+            "S" => Self::SHIFT,
+            "A" | "a" | "M" | "m" => Self::ALT,
+            "s" | "D" | "d" => Self::SUPER,
+            "H" | "h" => Self::HYPER,
+            _ => match name.to_lowercase().as_str() {
+                "control" => Self::CTRL,
+                "option" => Self::ALT,
+                "win" | "cmd" => Self::SUPER,
+                name => return Self::NAMED
+                    .into_iter()
+                    .find(|(_, n)| n.eq(&name))
+                    .map(|(m, _)| m)
             }
+        };
+
+        Some(m)
+    }
+}
+impl Display for KeyMod {
+    /// Writes modifier names joined with `+`, for example `ctrl+shift`
+    /// Writes `any` for [KeyMod::ANY] and `none` for [KeyMod::NONE]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_any() {
+            write!(f, "any")
+        } else if self.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", self.names().collect::<Vec<_>>().join("+"))
+        }
+    }
+}
+impl FromStr for KeyMod {
+    type Err = ParseKeyError;
+
+    /// Parses modifier names (see [KeyMod::from_name]) joined with `+`, `-` or `_`
+    /// An empty string and `any` are parsed as [KeyMod::ANY]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" | "any" => return Ok(Self::ANY),
+            "none" => return Ok(Self::NONE),
+            _ => ()
+        }
+
+        let mut mods = Self::NONE;
+        for name in s.trim().split(['+', '-', '_']) {
+            let Some(m) = Self::from_name(name) else {
+                return Err(ParseKeyError::new(format!("unknown key modifier \"{}\"", name)));
+            };
+            mods.insert(m);
         }
+
+        Ok(mods)
+    }
+}
+impl Default for KeyMod {
+    /// Returns [KeyMod::ANY], so default keys match any modifiers
+    fn default() -> Self {
+        Self::ANY
+    }
+}
+impl std::fmt::Debug for KeyMod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_any() {
+            return write!(f, "KeyMod(ANY)");
+        }
+        if self.is_empty() {
+            return write!(f, "KeyMod(NONE)");
+        }
+
+        write!(f, "KeyMod({})", self.names().collect::<Vec<_>>().join(" | ").to_uppercase())
+    }
+}
+impl std::ops::BitOr for KeyMod {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+impl std::ops::BitOrAssign for KeyMod {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs)
+    }
+}
+impl std::ops::BitAnd for KeyMod {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+impl std::ops::Sub for KeyMod {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 & !rhs.0)
     }
 }
 impl From<(bool, bool, bool)> for KeyMod {
     fn from(value: (bool, bool, bool)) -> Self {
-        match value {
-            (true,  false, false) => Self::Shift,
-            (false, true,  false) => Self::Ctrl,
-            (false, false, true)  => Self::Alt,
-            (true,  true,  false) => Self::ShiftCtrl,
-            (true,  false, true)  => Self::ShiftAlt,
-            (true,  true,  true)  => Self::ShiftCtrlAlt,
-            (false, true,  true)  => Self::CtrlAlt,
-            (false, false, false) => Self::None
+        let mut mods = Self::NONE;
+        mods.set(Self::SHIFT, value.0);
+        mods.set(Self::CTRL, value.1);
+        mods.set(Self::ALT, value.2);
+        mods
+    }
+}
+impl From<KeyMod> for (bool, bool, bool) {
+    fn from(value: KeyMod) -> Self {
+        value.tuple()
+    }
+}
+
+#[cfg(feature="serde")]
+impl serde::Serialize for KeyMod {
+    /// Serializes using [Display], for example `ctrl+shift`
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
+}
+#[cfg(feature="serde")]
+impl<'de> serde::Deserialize<'de> for KeyMod {
+    /// Deserializes using [FromStr]
+    /// Also accepts the old `snake_case` form, for example `shift_ctrl`
+    fn deserialize<D: serde::Deserializer<'de>>(des: D) -> Result<Self, D::Error> {
+        des.deserialize_str(FromStrVisitor::<Self>::new("key modifiers, for example \"ctrl+shift\""))
+    }
+}
+
+/// Visitor that deserializes a string using [FromStr]
+#[cfg(feature="serde")]
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    _marker: std::marker::PhantomData<T>
+}
+#[cfg(feature="serde")]
+impl<T> FromStrVisitor<T> {
+    fn new(expecting: &'static str) -> Self {
+        Self {
+            expecting,
+            _marker: std::marker::PhantomData
         }
     }
 }
-impl Into<(bool, bool, bool)> for KeyMod {
-    fn into(self) -> (bool, bool, bool) {
-        self.tuple()
+#[cfg(feature="serde")]
+impl<'de, T> serde::de::Visitor<'de> for FromStrVisitor<T>
+where T: FromStr,
+      T::Err: Display
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.expecting)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where E: serde::de::Error
+    {
+        T::from_str(v).map_err(E::custom)
     }
 }
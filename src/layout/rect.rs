@@ -324,6 +324,25 @@ impl Rect {
     pub fn area(&self) -> u16 {
         self.width.saturating_mul(self.height)
     }
+    /// Returns `true` if the position `(x, y)` is inside the rect
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tuich::layout::*;
+    /// let r = Rect::new(1, 2, 10, 20);
+    ///
+    /// assert!(r.contains((1, 2)));
+    /// assert!(r.contains((10, 21)));
+    /// assert!(!r.contains((11, 2)));
+    /// assert!(!r.contains((0, 2)));
+    /// ```
+    pub fn contains(&self, pos: (u16, u16)) -> bool {
+        let (x, y) = pos;
+        x >= self.x && y >= self.y
+            && x < self.x.saturating_add(self.width)
+            && y < self.y.saturating_add(self.height)
+    }
 
     /// Returns left border rect `(x, y, 1, height)`
     ///
@@ -444,4 +463,12 @@ mod tests {
         assert_eq!(r.pos(), (1, 3), "Position (x, y)");
         assert_eq!(r.size(), (10, 20), "Size (width, height)");
     }
+    #[test]
+    fn contains_at_edge() {
+        let r = Rect::new(u16::MAX - 1, u16::MAX - 1, 10, 10);
+
+        assert!(r.contains((u16::MAX - 1, u16::MAX - 1)));
+        assert!(!r.contains((u16::MAX, u16::MAX)), "The right and bottom sides are saturated");
+        assert!(!r.contains((0, 0)));
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::{self, Debug};
+
 use crate::{backend::{BackendAltScreen, BackendClassicMode, BackendMouse, BackendRawMode}, layout::Rect};
 #[allow(unused_imports)]
 use crate::{backend::Backend, buffer::{Buffer, Cell}};
@@ -5,16 +7,32 @@ use crate::{backend::Backend, buffer::{Buffer, Cell}};
 #[cfg(feature="backend-event")]
 use crate::backend::BackendEvent;
 
+/// Resize callback
+/// Receives the new terminal size `(width, height)`
+pub type ResizeCallback = Box<dyn FnMut(u16, u16) + Send>;
+
 /// Terminal
-#[derive(Debug, Clone)]
+///
+/// # Notes
+///
+/// - resize callbacks are not cloned, a cloned [Terminal] has none
 pub struct Terminal<B: Backend> {
     pub backend: B,
-    pub buffer: Buffer
+    pub buffer: Buffer,
+    /// Whether to check the terminal size before each draw
+    pub auto_resize: bool,
+
+    resize_callbacks: Vec<ResizeCallback>,
 }
 impl<B: Backend> Terminal<B> {
     /// Create a blank [Terminal]
     pub fn new(backend: B, buffer: Buffer) -> Self {
-        Self { backend, buffer }
+        Self {
+            backend,
+            buffer,
+            auto_resize: false,
+            resize_callbacks: vec![],
+        }
     }
     /// Create a [Terminal] with a [Buffer] filled with an empty [Cell]
     pub fn empty(backend: B) -> Result<Self, B::Error> {
@@ -24,10 +42,73 @@ impl<B: Backend> Terminal<B> {
 
     //
 
+    /// Set whether to check the terminal size before each draw
+    /// When the size has changed, the buffer will be resized and the screen will be fully repainted
+    pub fn auto_resize(mut self, value: bool) -> Self {
+        self.auto_resize = value;
+        self
+    }
+    /// Add a callback that will be called after the buffer has been resized by [Terminal::check_size]
+    pub fn on_resize<F: FnMut(u16, u16) + Send + 'static>(mut self, callback: F) -> Self {
+        self.resize_callbacks.push(Box::new(callback));
+        self
+    }
+
+    //
+
     /// Draw terminal buffer
+    ///
+    /// # Notes
+    ///
+    /// - if [Terminal::auto_resize] is enabled and the size has changed,
+    ///   the buffer will be cleared before drawing, use [Terminal::draw_with] to avoid this
     pub fn draw(&mut self) -> Result<(), B::Error> {
+        if self.auto_resize {
+            self.check_size()?;
+        }
+
         self.backend.write_buffer(&self.buffer)
     }
+    /// Clear the buffer, fill it using `f` and draw it
+    /// If [Terminal::auto_resize] is enabled, the size will be checked before `f` is called
+    pub fn draw_with<F>(&mut self, f: F) -> Result<(), B::Error>
+    where F: FnOnce(&mut Buffer, Rect)
+    {
+        if self.auto_resize {
+            self.check_size()?;
+        }
+
+        self.buffer.clear();
+        let rect = self.buffer.rect();
+        f(&mut self.buffer, rect);
+
+        self.backend.write_buffer(&self.buffer)
+    }
+    /// Compare the terminal size with the buffer size and resize the buffer if they differ
+    /// Calls resize callbacks after resizing
+    /// Returns whether the buffer has been resized or not
+    pub fn check_size(&mut self) -> Result<bool, B::Error> {
+        let resized = self.update_size()?;
+        if resized {
+            self.backend.clear()?;
+        }
+
+        Ok(resized)
+    }
+    /// Same as [Terminal::check_size], but leaves clearing the terminal screen to the caller
+    pub(crate) fn update_size(&mut self) -> Result<bool, B::Error> {
+        let (width, height) = B::terminal_size()?;
+        if (width, height) == self.buffer.size() {
+            return Ok(false);
+        }
+
+        self.buffer.resize(width, height);
+        for callback in &mut self.resize_callbacks {
+            callback(width, height);
+        }
+
+        Ok(true)
+    }
     /// Resize the buffer and clear the terminal screen
     pub fn resize(&mut self, width: u16, height: u16) -> Result<(), B::Error> {
         self.backend.clear()?;
@@ -99,8 +180,130 @@ impl<B: BackendEvent + Backend> BackendEvent for Terminal<B> {
     }
 }
 
+impl<B: Backend + Clone> Clone for Terminal<B> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            buffer: self.buffer.clone(),
+            auto_resize: self.auto_resize,
+            resize_callbacks: vec![],
+        }
+    }
+}
+
+impl<B: Backend + Debug> Debug for Terminal<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Terminal")
+            .field("backend", &self.backend)
+            .field("buffer", &self.buffer)
+            .field("auto_resize", &self.auto_resize)
+            .field("resize_callbacks", &self.resize_callbacks.len())
+            .finish()
+    }
+}
+
 impl<B: Backend> Drop for Terminal<B> {
     fn drop(&mut self) {
         let _ = self.backend.reset();
     }
 }
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{cell::Cell as StdCell, sync::{Arc, Mutex}};
+
+    use crate::style::Style;
+
+    thread_local! {
+        static SIZE: StdCell<(u16, u16)> = const { StdCell::new((4, 2)) };
+    }
+
+    /// Backend with a size set by the test, counts screen clears and drawn buffers
+    #[derive(Debug, Default, Clone)]
+    struct TestBackend {
+        clears: usize,
+        flushes: usize,
+    }
+    impl Backend for TestBackend {
+        type Error = ();
+
+        fn write<S: AsRef<str>>(&mut self, _: S) -> Result<(), ()> { Ok(()) }
+        fn flush(&mut self) -> Result<(), ()> {
+            self.flushes += 1;
+            Ok(())
+        }
+        fn clear(&mut self) -> Result<(), ()> {
+            self.clears += 1;
+            Ok(())
+        }
+        fn show_cursor(&mut self) -> Result<(), ()> { Ok(()) }
+        fn hide_cursor(&mut self) -> Result<(), ()> { Ok(()) }
+        fn place_cursor(&mut self, _: u16, _: u16) -> Result<(), ()> { Ok(()) }
+        fn write_style(&mut self, _: &Style, _: &Style) -> Result<(), ()> { Ok(()) }
+        fn write_reset(&mut self) -> Result<(), ()> { Ok(()) }
+        fn reset(&mut self) -> Result<(), ()> { Ok(()) }
+
+        fn terminal_size() -> Result<(u16, u16), ()> {
+            Ok(SIZE.with(|s| s.get()))
+        }
+    }
+
+    #[test]
+    fn check_size() {
+        SIZE.with(|s| s.set((4, 2)));
+        let sizes = Arc::new(Mutex::new(vec![]));
+        let callback_sizes = sizes.clone();
+        let mut term = Terminal::empty(TestBackend::default())
+            .unwrap()
+            .on_resize(move |w, h| callback_sizes.lock().unwrap().push((w, h)));
+
+        // Same size, nothing to do
+        assert_eq!(term.check_size(), Ok(false));
+        assert_eq!(term.backend.clears, 0);
+
+        SIZE.with(|s| s.set((6, 3)));
+        assert_eq!(term.check_size(), Ok(true));
+        assert_eq!(term.size(), (6, 3));
+        assert_eq!(term.backend.clears, 1);
+        assert_eq!(*sizes.lock().unwrap(), [(6, 3)]);
+
+        assert_eq!(term.check_size(), Ok(false));
+        assert_eq!(term.backend.clears, 1);
+
+        // Callbacks are not cloned
+        let mut cloned = term.clone();
+        SIZE.with(|s| s.set((2, 2)));
+        assert_eq!(cloned.check_size(), Ok(true));
+        assert_eq!(sizes.lock().unwrap().len(), 1);
+        fn is_send<T: Send>() {}
+        is_send::<Terminal<TestBackend>>();
+    }
+
+    #[test]
+    fn auto_resize() {
+        SIZE.with(|s| s.set((4, 2)));
+        let mut term = Terminal::empty(TestBackend::default()).unwrap();
+
+        // Disabled by default
+        SIZE.with(|s| s.set((5, 2)));
+        term.draw().unwrap();
+        assert_eq!(term.size(), (4, 2));
+        assert_eq!(term.backend.clears, 0);
+
+        let mut term = term.auto_resize(true);
+        let mut drawn_rect = Rect::default();
+        term.draw_with(|_, rect| drawn_rect = rect).unwrap();
+        assert_eq!(drawn_rect, Rect::new(0, 0, 5, 2));
+        assert_eq!(term.backend.clears, 1);
+
+        // The screen is repainted fully only when the size has changed
+        term.draw().unwrap();
+        term.draw_with(|_, _| {}).unwrap();
+        assert_eq!(term.backend.clears, 1);
+        assert_eq!(term.backend.flushes, 4);
+    }
+}
@@ -29,3 +29,13 @@ pub use paragraph::Paragraph;
 pub mod prompt;
 #[cfg(feature="widget-prompt")]
 pub use prompt::Prompt;
+
+#[cfg(feature="widget-textarea")]
+pub mod textarea;
+#[cfg(feature="widget-textarea")]
+pub use textarea::TextArea;
+
+#[cfg(feature="widget-select-list")]
+pub mod select_list;
+#[cfg(feature="widget-select-list")]
+pub use select_list::SelectList;
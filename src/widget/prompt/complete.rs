@@ -0,0 +1,263 @@
+use std::{fs, ops::Range, path::{Path, PathBuf}};
+
+/// Completion candidate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Text that replaces the range
+    pub text: String,
+    /// Byte range of the value that is replaced
+    pub range: Range<usize>,
+    /// Text shown in the dropdown instead of [Candidate::text]
+    pub display: Option<String>,
+}
+impl Candidate {
+    pub fn new<S: ToString>(text: S, range: Range<usize>) -> Self {
+        Self {
+            text: text.to_string(),
+            range,
+            display: None,
+        }
+    }
+
+    //
+
+    /// Set text shown in the dropdown
+    pub fn display<S: ToString>(mut self, display: S) -> Self {
+        self.display = Some(display.to_string());
+        self
+    }
+
+    /// Text shown in the dropdown
+    pub fn label(&self) -> &str {
+        self.display.as_deref().unwrap_or(&self.text)
+    }
+}
+
+/// Completion provider
+/// Returns candidates for the prompt `value` with the cursor at the byte offset `cursor`
+///
+/// Implemented for closures, so completers can be combined easily
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::*;
+/// let completer = |value: &str, cursor: usize| {
+///     if value[..cursor].ends_with('@') {
+///         vec![Candidate::new("@user", cursor - 1..cursor)]
+///     } else {
+///         vec![]
+///     }
+/// };
+///
+/// assert_eq!(completer.complete("hi @", 4), vec![Candidate::new("@user", 3..4)]);
+/// ```
+pub trait Completer {
+    fn complete(&self, value: &str, cursor: usize) -> Vec<Candidate>;
+}
+impl<F: Fn(&str, usize) -> Vec<Candidate>> Completer for F {
+    fn complete(&self, value: &str, cursor: usize) -> Vec<Candidate> {
+        self(value, cursor)
+    }
+}
+
+/// Byte range of the whitespace separated word that ends at the cursor
+pub fn word_before(value: &str, cursor: usize) -> Range<usize> {
+    let start = value[..cursor]
+        .rfind(char::is_whitespace)
+        .map_or(0, |i| i + value[i..].chars().next().map_or(1, char::len_utf8));
+
+    start..cursor
+}
+
+/// List completer
+/// Completes the word before the cursor with items starting with it, for example commands or enum values
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::*;
+/// let completer = ListCompleter::new(["build", "bench", "test"]);
+/// let texts: Vec<_> = completer.complete("cargo b", 7).into_iter().map(|c| c.text).collect();
+///
+/// assert_eq!(texts, ["build", "bench"]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ListCompleter {
+    pub items: Vec<String>,
+    /// Whether to ignore case when matching
+    pub ignore_case: bool,
+    /// Whether to complete the whole value before the cursor instead of the last word
+    pub whole: bool,
+}
+impl ListCompleter {
+    pub fn new<I>(items: I) -> Self
+    where I: IntoIterator,
+          I::Item: ToString
+    {
+        Self {
+            items: items.into_iter().map(|i| i.to_string()).collect(),
+            ignore_case: false,
+            whole: false,
+        }
+    }
+
+    //
+
+    /// Set whether to ignore case when matching
+    pub fn ignore_case(mut self, value: bool) -> Self {
+        self.ignore_case = value;
+        self
+    }
+    /// Set whether to complete the whole value before the cursor instead of the last word
+    pub fn whole(mut self, value: bool) -> Self {
+        self.whole = value;
+        self
+    }
+}
+impl Completer for ListCompleter {
+    fn complete(&self, value: &str, cursor: usize) -> Vec<Candidate> {
+        let range = if self.whole { 0..cursor } else { word_before(value, cursor) };
+        let typed = &value[range.clone()];
+
+        self.items
+            .iter()
+            .filter(|item| *item != typed && starts_with(item, typed, self.ignore_case))
+            .map(|item| Candidate::new(item, range.clone()))
+            .collect()
+    }
+}
+
+fn starts_with(s: &str, prefix: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        s.to_lowercase().starts_with(&prefix.to_lowercase())
+    } else {
+        s.starts_with(prefix)
+    }
+}
+
+/// File path completer
+/// Completes the word before the cursor with paths of the file system
+///
+/// Directories end with `/`, so completing them again lists their entries.
+/// Hidden files are listed only when the typed name starts with `.`, unless [PathCompleter::show_hidden] is enabled
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PathCompleter {
+    /// Directory relative paths start from, the current directory if `None`
+    pub base: Option<PathBuf>,
+    /// Whether to list only directories
+    pub dirs_only: bool,
+    /// Whether to always list hidden files
+    pub show_hidden: bool,
+}
+impl PathCompleter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //
+
+    /// Set directory relative paths start from
+    pub fn base<P: Into<PathBuf>>(mut self, base: P) -> Self {
+        self.base = Some(base.into());
+        self
+    }
+    /// Set whether to list only directories
+    pub fn dirs_only(mut self, value: bool) -> Self {
+        self.dirs_only = value;
+        self
+    }
+    /// Set whether to always list hidden files
+    pub fn show_hidden(mut self, value: bool) -> Self {
+        self.show_hidden = value;
+        self
+    }
+}
+impl Completer for PathCompleter {
+    fn complete(&self, value: &str, cursor: usize) -> Vec<Candidate> {
+        let range = word_before(value, cursor);
+        let typed = &value[range.clone()];
+
+        // Split into the directory and the start of a name
+        let (dir, name) = match typed.rfind('/') {
+            Some(i) => typed.split_at(i + 1),
+            None => ("", typed),
+        };
+
+        let dir_path = match (&self.base, Path::new(dir)) {
+            (_, path) if path.is_absolute() => path.to_path_buf(),
+            (Some(base), path) => base.join(path),
+            (None, path) => Path::new(".").join(path),
+        };
+        let Ok(entries) = fs::read_dir(dir_path) else {
+            return vec![];
+        };
+
+        let mut candidates: Vec<Candidate> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                let is_dir = entry.path().is_dir();
+
+                if !file_name.starts_with(name)
+                || self.dirs_only && !is_dir
+                || file_name.starts_with('.') && !name.starts_with('.') && !self.show_hidden {
+                    return None;
+                }
+
+                let slash = if is_dir { "/" } else { "" };
+                Some(
+                    Candidate::new(format!("{dir}{file_name}{slash}"), range.clone())
+                        .display(format!("{file_name}{slash}"))
+                )
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| a.text.cmp(&b.text));
+        candidates
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word() {
+        assert_eq!(word_before("git checkout ma", 15), 13..15);
+        assert_eq!(word_before("git  ", 5), 5..5);
+        assert_eq!(word_before("日本\u{3000}語", 12), 9..12);
+        assert_eq!(word_before("abc", 2), 0..2);
+    }
+
+    #[test]
+    fn list() {
+        let completer = ListCompleter::new(["Red", "Green", "Reset"]).ignore_case(true).whole(true);
+        let texts: Vec<_> = completer.complete("re", 2).into_iter().map(|c| c.text).collect();
+
+        assert_eq!(texts, ["Red", "Reset"]);
+        assert!(completer.complete("Red", 3).is_empty(), "Already complete");
+    }
+
+    #[test]
+    fn path() {
+        let base = std::env::temp_dir().join(format!("tuich_complete_{}", std::process::id()));
+        fs::create_dir_all(base.join("src/widget")).unwrap();
+        fs::write(base.join("src/lib.rs"), "").unwrap();
+        fs::write(base.join("src/.hidden"), "").unwrap();
+
+        let completer = PathCompleter::new().base(&base);
+        let texts = |value: &str| -> Vec<String> {
+            completer.complete(value, value.len()).into_iter().map(|c| c.text).collect()
+        };
+
+        assert_eq!(texts("cat s"), ["src/"]);
+        assert_eq!(texts("cat src/"), ["src/lib.rs", "src/widget/"]);
+        assert_eq!(texts("cat src/."), ["src/.hidden"]);
+        assert_eq!(completer.clone().dirs_only(true).complete("src/", 4)[0].label(), "widget/");
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
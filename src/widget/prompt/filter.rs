@@ -0,0 +1,107 @@
+use std::{fmt, sync::Arc};
+
+/// Input filter
+/// Accepts or rejects each inserted grapheme
+///
+/// Implemented for closures
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::*;
+/// let mut state = PromptState::default()
+///     .filter(CharFilter::Digits)
+///     .filter(|g: &str| g != "0");
+///
+/// state.action(PromptAction::String("a1b02".into()));
+/// assert_eq!(state.value(), "12");
+/// ```
+pub trait InputFilter {
+    fn accept(&self, grapheme: &str) -> bool;
+}
+impl<F: Fn(&str) -> bool> InputFilter for F {
+    fn accept(&self, grapheme: &str) -> bool {
+        self(grapheme)
+    }
+}
+
+/// Common input filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharFilter {
+    /// ASCII digits
+    Digits,
+    /// ASCII digits, `-`, `+`, `.` and `e` for floats
+    Numeric,
+    /// Letters and digits
+    Alphanumeric,
+    /// Letters, digits and `_`
+    Identifier,
+    /// Printable ASCII chars
+    Ascii,
+    /// Anything but whitespace
+    NoWhitespace,
+}
+impl InputFilter for CharFilter {
+    fn accept(&self, grapheme: &str) -> bool {
+        // Marks are checked with the char they are attached to
+        let Some(char) = grapheme.chars().next() else {
+            return false;
+        };
+        let single = grapheme.len() == char.len_utf8();
+
+        match self {
+            Self::Digits => single && char.is_ascii_digit(),
+            Self::Numeric => single && (char.is_ascii_digit() || matches!(char, '-' | '+' | '.' | 'e' | 'E')),
+            Self::Alphanumeric => char.is_alphanumeric(),
+            Self::Identifier => char.is_alphanumeric() || char == '_',
+            Self::Ascii => single && (char.is_ascii_graphic() || char == ' '),
+            Self::NoWhitespace => !char.is_whitespace(),
+        }
+    }
+}
+
+/// Value validator
+/// Returns an error message if the value is invalid
+///
+/// Implemented for closures
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::*;
+/// let mut state = PromptState::new("8080").validator(|value: &str| {
+///     value.parse::<u16>().map(|_| ()).map_err(|_| "Not a port".to_string())
+/// });
+/// assert!(state.is_valid());
+///
+/// state.action(PromptAction::Char('0'));
+/// assert_eq!(state.error(), Some("Not a port"));
+/// ```
+pub trait Validator {
+    fn validate(&self, value: &str) -> Result<(), String>;
+}
+impl<F: Fn(&str) -> Result<(), String>> Validator for F {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        self(value)
+    }
+}
+
+/// Shared filter or validator
+/// States are equal if they share the same filters
+pub(super) struct Shared<T: ?Sized>(pub(super) Arc<T>);
+impl<T: ?Sized> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+impl<T: ?Sized> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl<T: ?Sized> Eq for Shared<T> {}
+impl<T: ?Sized> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Shared(..)")
+    }
+}
@@ -0,0 +1,30 @@
+use crate::text::Span;
+
+/// Prompt value highlighter
+/// Returns spans with the value split into styled pieces, their contents should add up to the value
+///
+/// Implemented for functions, spans are drawn over the prompt style
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::{style::Color, text::Span, widget::prompt::*};
+/// // Colorize the command of a shell-like line
+/// fn command(value: &str) -> Vec<Span<'_>> {
+///     let end = value.find(' ').unwrap_or(value.len());
+///     vec![(&value[..end], Color::Green).into(), (&value[end..]).into()]
+/// }
+///
+/// let state = PromptState::new("cargo build").highlighter(command);
+/// assert_eq!(state.highlight()[0], Span::new("cargo", Color::Green));
+/// ```
+pub trait Highlighter {
+    fn highlight<'a>(&self, value: &'a str) -> Vec<Span<'a>>;
+}
+impl<F> Highlighter for F
+where F: for<'a> Fn(&'a str) -> Vec<Span<'a>>
+{
+    fn highlight<'a>(&self, value: &'a str) -> Vec<Span<'a>> {
+        self(value)
+    }
+}
@@ -0,0 +1,225 @@
+use std::{fs, io, path::Path};
+
+/// Default [History::max_len]
+pub const DEFAULT_HISTORY_LEN: usize = 1000;
+
+/// How [History] handles duplicate entries
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum HistoryDedup {
+    /// Keep all entries
+    Off,
+    /// Skip an entry equal to the latest one
+    Consecutive,
+    /// Remove older entries equal to the new one
+    #[default]
+    All,
+}
+
+/// Prompt input history
+/// Entries are stored from the oldest to the latest
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::History;
+/// let mut history = History::new();
+/// history.push("cargo build");
+/// history.push("cargo test");
+/// history.push("cargo build");
+///
+/// assert_eq!(history.entries(), ["cargo test", "cargo build"]);
+/// assert_eq!(history.search("test", history.len()), Some(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all="snake_case"))]
+pub struct History {
+    /// Max number of entries, the oldest ones are removed first
+    pub max_len: usize,
+    /// How to handle duplicate entries
+    pub dedup: HistoryDedup,
+
+    entries: Vec<String>,
+}
+impl History {
+    pub fn new() -> Self {
+        Self {
+            max_len: DEFAULT_HISTORY_LEN,
+            dedup: HistoryDedup::default(),
+            entries: vec![],
+        }
+    }
+
+    //
+
+    /// Set max number of entries
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = len;
+        self.truncate();
+        self
+    }
+    /// Set how to handle duplicate entries
+    pub fn dedup(mut self, dedup: HistoryDedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Add an entry
+    /// Empty entries are ignored
+    pub fn push<S: ToString>(&mut self, entry: S) {
+        let entry = entry.to_string();
+        if entry.is_empty() {
+            return;
+        }
+
+        match self.dedup {
+            HistoryDedup::Off => (),
+            HistoryDedup::Consecutive => if self.latest() == Some(&entry) {
+                return;
+            },
+            HistoryDedup::All => self.entries.retain(|e| *e != entry),
+        }
+
+        self.entries.push(entry);
+        self.truncate();
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    fn truncate(&mut self) {
+        let extra = self.entries.len().saturating_sub(self.max_len);
+        self.entries.drain(..extra);
+    }
+
+    /// Index of the latest entry before `before` that contains `query`
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let before = before.min(self.len());
+        self.entries[..before].iter().rposition(|e| e.contains(query))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&String> {
+        self.entries.get(index)
+    }
+    pub fn latest(&self) -> Option<&String> {
+        self.entries.last()
+    }
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // File
+
+    /// Load entries from a file, adding them after the existing ones
+    /// A missing file is not an error
+    ///
+    /// # Notes
+    ///
+    /// - the file has an entry per line, newlines and backslashes in entries are escaped with `\`
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for line in content.lines() {
+            self.push(unescape(line));
+        }
+
+        Ok(())
+    }
+    /// Save entries to a file, overwriting it
+    /// See [History::load] for the format
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&escape(entry));
+            content.push('\n');
+        }
+
+        fs::write(path, content)
+    }
+}
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<S: ToString> FromIterator<S> for History {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut history = Self::new();
+        for entry in iter {
+            history.push(entry);
+        }
+        history
+    }
+}
+
+fn escape(entry: &str) -> String {
+    entry
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+fn unescape(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            entry.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some('r') => entry.push('\r'),
+            Some(char) => entry.push(char),
+            None => entry.push('\\'),
+        }
+    }
+
+    entry
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup() {
+        let mut history = History::new().dedup(HistoryDedup::Consecutive).max_len(3);
+        for entry in ["a", "a", "b", "", "a", "c"] {
+            history.push(entry);
+        }
+        assert_eq!(history.entries(), ["b", "a", "c"]);
+
+        let mut history = History::new().dedup(HistoryDedup::All);
+        for entry in ["a", "b", "a"] {
+            history.push(entry);
+        }
+        assert_eq!(history.entries(), ["b", "a"]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("tuich_history_{}", std::process::id()));
+        let history: History = ["one", "two\nlines", "back\\slash\\n"].into_iter().collect();
+
+        history.save(&path).unwrap();
+        let mut loaded = History::new();
+        loaded.load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, history);
+        assert!(loaded.load(&path).is_ok(), "Missing file");
+    }
+}
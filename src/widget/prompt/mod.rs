@@ -0,0 +1,2533 @@
+mod complete;
+mod filter;
+mod highlight;
+mod history;
+#[cfg(feature = "backend-event")]
+mod vi;
+mod word;
+
+pub use complete::*;
+pub use filter::*;
+pub use highlight::*;
+pub use history::*;
+#[cfg(feature = "backend-event")]
+pub use vi::*;
+pub use word::*;
+
+use crate::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::{BorderKind, Color, Style},
+    text::Span,
+};
+
+use super::{Borders, Draw};
+
+use std::{collections::VecDeque, ops::Range, sync::Arc, usize};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[cfg(feature = "backend-event")]
+use crate::event::{Event, Key, Mouse, MouseBtn};
+
+/// Prompt message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptAction {
+    /// Push char into prompt
+    Char(char),
+    /// Push string into prompt
+    String(String),
+
+    /// Delete chars to the left
+    DeleteLeft(usize),
+    /// Delete chars to the right
+    DeleteRight(usize),
+    /// Delete next word
+    DeleteNextWord,
+    /// Delete previous word
+    DeletePrevWord,
+    /// Delete all from the cursor pos to the start of prompt
+    DeleteToStart,
+    /// Delete all from the cursor pos to the end of prompt
+    DeleteToEnd,
+    /// Delete everything
+    Clear,
+
+    /// Move the cursor left
+    MoveLeft(usize),
+    /// Move the cursor right
+    MoveRight(usize),
+    /// Move the cursor to the end of a word
+    MoveNextWord,
+    /// Move the cursor to the start of a word
+    MovePrevWord,
+    /// Move the cursor to the start of the prompt
+    MoveStart,
+    /// Move the cursor to the end of the prompt
+    MoveEnd,
+    /// Move the cursor to the grapheme index in prompt
+    /// It's not a display column, a wide char takes one position but two columns
+    MoveTo(usize),
+
+    /// Extend the selection to the left
+    SelectLeft(usize),
+    /// Extend the selection to the right
+    SelectRight(usize),
+    /// Extend the selection to the end of a word
+    SelectNextWord,
+    /// Extend the selection to the start of a word
+    SelectPrevWord,
+    /// Extend the selection to the start of the prompt
+    SelectStart,
+    /// Extend the selection to the end of the prompt
+    SelectEnd,
+    /// Set the mark at the cursor, so moving the cursor selects text
+    /// Deactivates the mark if it's already set
+    SetMark,
+    /// Deactivate the mark and clear the selection
+    ClearSelection,
+
+    /// Insert the last killed text
+    Yank,
+    /// Replace the just yanked text with the previous killed text
+    YankPop,
+
+    /// Undo the last change
+    Undo,
+    /// Redo the last undone change
+    Redo,
+
+    /// Replace the value with the previous history entry
+    HistoryPrev,
+    /// Replace the value with the next history entry, or with the line being typed
+    HistoryNext,
+    /// Same as [PromptAction::HistoryPrev], but only entries starting with the line being typed
+    HistoryPrefixPrev,
+    /// Same as [PromptAction::HistoryNext], but only entries starting with the line being typed
+    HistoryPrefixNext,
+    /// Start the reverse incremental history search, or find the next older match
+    SearchHistory,
+
+    /// Replace the completed text with the next candidate
+    /// See [PromptState::complete]
+    CompleteNext,
+    /// Replace the completed text with the previous candidate
+    CompletePrev,
+    /// Insert the inline suggestion, see [PromptState::suggestion]
+    AcceptSuggestion,
+}
+impl PromptAction {
+    /// Whether the action keeps the selection and moves its end
+    fn selects(&self) -> bool {
+        matches!(
+            self,
+            Self::SelectLeft(_) |
+            Self::SelectRight(_) |
+            Self::SelectNextWord |
+            Self::SelectPrevWord |
+            Self::SelectStart |
+            Self::SelectEnd |
+            Self::SetMark
+        )
+    }
+    /// Whether the action puts deleted text to the kill ring
+    fn kills(&self) -> bool {
+        matches!(
+            self,
+            Self::DeleteNextWord |
+            Self::DeletePrevWord |
+            Self::DeleteToStart |
+            Self::DeleteToEnd
+        )
+    }
+}
+
+/// How to insert pasted newlines
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum PasteNewlines {
+    /// Replace newlines with spaces
+    #[default]
+    Space,
+    /// Remove newlines
+    Strip,
+    /// Insert only the first line
+    FirstLine,
+    /// Insert newlines as is
+    Keep,
+}
+impl PasteNewlines {
+    /// Apply to pasted text
+    /// `\r\n` and `\r` are treated as newlines too
+    pub fn apply(self, text: &str) -> String {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        match self {
+            Self::Space => text.replace('\n', " "),
+            Self::Strip => text.replace('\n', ""),
+            Self::FirstLine => text.lines().next().unwrap_or_default().to_string(),
+            Self::Keep => text,
+        }
+    }
+}
+
+/// Grapheme cluster of the prompt value
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Grapheme {
+    /// Byte offset in the value
+    start: usize,
+    /// Display width
+    width: usize,
+}
+impl Grapheme {
+    fn segment(s: &str, offset: usize) -> impl Iterator<Item = Grapheme> + '_ {
+        s.grapheme_indices(true)
+            .map(move |(i, g)| Grapheme { start: offset + i, width: g.width() })
+    }
+}
+
+/// Default [KillRing::max_len]
+pub const DEFAULT_KILL_RING_LEN: usize = 60;
+
+/// How to draw a secret value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum PromptMask {
+    /// Draw each grapheme as the char
+    Char(char),
+    /// Draw nothing, the cursor stays at the start
+    Hidden,
+}
+
+/// Kill ring
+/// Killed (cut) text that can be yanked (pasted) back, like in emacs
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::KillRing;
+/// let mut ring = KillRing::new();
+/// ring.push("one");
+/// ring.push("two");
+/// assert_eq!(ring.current(), Some("two"));
+///
+/// ring.rotate();
+/// assert_eq!(ring.current(), Some("one"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillRing {
+    /// Max number of entries
+    pub max_len: usize,
+
+    /// The latest entry is the first
+    entries: VecDeque<String>,
+    /// Index of the entry to yank
+    index: usize,
+}
+impl KillRing {
+    pub fn new() -> Self {
+        Self {
+            max_len: DEFAULT_KILL_RING_LEN,
+            entries: VecDeque::new(),
+            index: 0,
+        }
+    }
+
+    //
+
+    /// Set max number of entries
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = len;
+        self.entries.truncate(len);
+        self
+    }
+
+    /// Add a new entry
+    pub fn push<S: ToString>(&mut self, text: S) {
+        self.entries.push_front(text.to_string());
+        self.entries.truncate(self.max_len);
+        self.index = 0;
+    }
+    /// Add text to the end of the latest entry
+    pub fn append(&mut self, text: &str) {
+        match self.entries.front_mut() {
+            Some(entry) => entry.push_str(text),
+            None => self.push(text),
+        }
+        self.index = 0;
+    }
+    /// Add text to the start of the latest entry
+    pub fn prepend(&mut self, text: &str) {
+        match self.entries.front_mut() {
+            Some(entry) => entry.insert_str(0, text),
+            None => self.push(text),
+        }
+        self.index = 0;
+    }
+    /// Make the previous entry current, wrapping to the latest one
+    pub fn rotate(&mut self) {
+        if !self.entries.is_empty() {
+            self.index = (self.index + 1) % self.entries.len();
+        }
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index = 0;
+    }
+
+    /// Entry to yank
+    pub fn current(&self) -> Option<&str> {
+        self.entries.get(self.index).map(String::as_str)
+    }
+    /// Entries from the latest
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+impl Default for KillRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the last action did, for joining kills and yank-pop
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum LastAction {
+    #[default]
+    Other,
+    Kill,
+    /// Yanked graphemes range
+    Yank(usize, usize),
+}
+
+/// Reverse incremental history search
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HistorySearch {
+    query: String,
+    /// Index of the matched entry
+    found: Option<usize>,
+    failed: bool,
+    /// Value and cursor before the search
+    line: String,
+    cursor: usize,
+}
+
+/// Completion candidates
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Completion {
+    candidates: Vec<Candidate>,
+    /// Index of the applied candidate when cycling
+    selected: Option<usize>,
+    /// Byte range of the applied candidate and the text it replaced
+    applied: Option<(Range<usize>, String)>,
+}
+
+/// Default [PromptState::undo_depth]
+pub const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// Replacement of text in the prompt value
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Change {
+    /// Byte offset in the value
+    start: usize,
+    removed: String,
+    inserted: String,
+}
+
+/// Undo step
+/// All changes made by a single action, or by consecutive typing
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UndoStep {
+    changes: Vec<Change>,
+    cursor_before: usize,
+    cursor_after: usize,
+    /// Whether typed chars can be added to this step
+    typing: bool,
+}
+
+/// Prompt state
+///
+/// # Notes
+///
+/// - positions and amounts (cursor, selection, deletions, etc.) are in grapheme clusters,
+///   so a wide char or a letter with combining marks is a single position
+/// - use [PromptState::col_of] and [PromptState::index_at_col] to convert positions to display columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptState {
+    /// How to insert pasted newlines
+    pub paste_newlines: PasteNewlines,
+    /// How to draw the value if it's a secret
+    pub mask: Option<PromptMask>,
+    /// Max value length in graphemes
+    pub max_len: Option<usize>,
+    /// Max value display width
+    pub max_width: Option<usize>,
+    /// Max number of undo steps
+    pub undo_depth: usize,
+    /// How word motions and deletions split the value into words
+    pub word_mode: WordMode,
+    /// Killed text, see [PromptAction::Yank]
+    pub kill_ring: KillRing,
+    /// Input history, see [PromptState::submit]
+    pub history: History,
+    /// Key bindings used by [PromptState::handle_keys]
+    #[cfg(feature = "backend-event")]
+    pub keymap: PromptKeymap,
+
+    value: String,
+    graphemes: Vec<Grapheme>,
+    /// Cursor position in graphemes
+    cursor: usize,
+    width: usize,
+    /// Selection start, the selection ends at the cursor
+    anchor: Option<usize>,
+    /// Whether the mark is set, so moving the cursor extends the selection
+    mark: bool,
+    last: LastAction,
+    /// Whether the mouse was pressed inside the prompt
+    dragging: bool,
+
+    undo: VecDeque<UndoStep>,
+    redo: Vec<UndoStep>,
+    /// Whether an action is being applied
+    in_action: bool,
+    /// Whether the current action has already started an undo step
+    step_open: bool,
+    /// Whether the current action is typing a char
+    typing: bool,
+
+    /// Index of the shown history entry
+    history_index: Option<usize>,
+    /// Line being typed before walking the history
+    history_draft: String,
+    search: Option<HistorySearch>,
+
+    completion: Option<Completion>,
+
+    filters: Vec<filter::Shared<dyn InputFilter + Send + Sync>>,
+    validator: Option<filter::Shared<dyn Validator + Send + Sync>>,
+    highlighter: Option<filter::Shared<dyn Highlighter + Send + Sync>>,
+    error: Option<String>,
+
+    #[cfg(feature = "backend-event")]
+    vi: vi::Vi,
+}
+impl PromptState {
+    pub fn new<V: ToString>(value: V) -> Self {
+        let mut state = Self::default();
+        state.set_value(value.to_string());
+        state.move_end();
+        state.clear_undo();
+        state
+    }
+
+    //
+
+    /// Set how to insert pasted newlines
+    pub fn paste_newlines(mut self, value: PasteNewlines) -> Self {
+        self.paste_newlines = value;
+        self
+    }
+    /// Set how to draw the value, for example for passwords
+    /// Display columns (see [PromptState::cursor_pos]) take the mask into account
+    pub fn mask(mut self, mask: PromptMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+    /// Add an input filter
+    /// Inserted graphemes are dropped unless all filters accept them
+    pub fn filter<F: InputFilter + Send + Sync + 'static>(mut self, filter: F) -> Self {
+        self.filters.push(filter::Shared(Arc::new(filter)));
+        self
+    }
+    /// Set max value length in graphemes
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = Some(len);
+        self
+    }
+    /// Set max value display width
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+    /// Set value validator
+    /// The value is validated after each change, see [PromptState::error]
+    pub fn validator<V: Validator + Send + Sync + 'static>(mut self, validator: V) -> Self {
+        self.set_validator(validator);
+        self
+    }
+    /// Set how word motions and deletions split the value into words
+    pub fn word_mode(mut self, mode: WordMode) -> Self {
+        self.word_mode = mode;
+        self
+    }
+    /// Set value highlighter, see [Prompt]
+    pub fn highlighter<H: Highlighter + Send + Sync + 'static>(mut self, highlighter: H) -> Self {
+        self.set_highlighter(highlighter);
+        self
+    }
+    /// Set max number of undo steps
+    pub fn undo_depth(mut self, depth: usize) -> Self {
+        self.undo_depth = depth;
+        while self.undo.len() > depth {
+            self.undo.pop_front();
+        }
+        self
+    }
+
+    /// Handle events
+    /// Handles key presses (see [PromptState::handle_keys]), pasted text and mouse (see [PromptState::handle_mouse])
+    /// `area` is the rect the value is drawn in (see [Prompt::text_rect])
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_event(&mut self, event: &Event, area: Rect) -> bool {
+        match event {
+            Event::Key(key, _, info) if info.is_press() => self.handle_keys(*key),
+            Event::Paste(text) => {
+                let text = self.paste_newlines.apply(text);
+                self.action(PromptAction::String(text))
+            },
+            Event::Mouse(mouse, _, _) => self.handle_mouse(*mouse, area),
+            _ => false
+        }
+    }
+    /// Handle mouse events
+    /// Left click places the cursor, dragging selects text
+    /// `area` is the rect the value is drawn in (see [Prompt::text_rect])
+    /// Returns whether the state has been updated or not
+    ///
+    /// # Notes
+    ///
+    /// - the horizontal scroll is calculated the same way as [Prompt] does when it's focused
+    #[cfg(feature = "backend-event")]
+    pub fn handle_mouse(&mut self, mouse: Mouse, area: Rect) -> bool {
+        match mouse {
+            Mouse::Down(_, MouseBtn::Left, x, y) if area.contains((x, y)) => {
+                let pos = self.pos_at(x, area);
+                self.dragging = true;
+                self.clear_selection();
+                self.move_to(pos)
+            },
+            Mouse::Drag(_, MouseBtn::Left, x, _) if self.dragging => {
+                let pos = self.pos_at(x, area);
+                self.anchor.get_or_insert(self.cursor);
+                self.move_to(pos)
+            },
+            Mouse::Up(_, MouseBtn::Left, _, _) if self.dragging => {
+                self.dragging = false;
+                if self.anchor == Some(self.cursor) {
+                    self.anchor = None;
+                }
+                false
+            },
+            _ => false
+        }
+    }
+    /// Cursor position under the column `x` of the screen
+    #[cfg(feature = "backend-event")]
+    fn pos_at(&self, x: u16, area: Rect) -> usize {
+        let scroll = self.scroll(area.width);
+        self.index_at_col(x.saturating_sub(area.x).saturating_add(scroll) as usize)
+    }
+
+    /// Handle key events
+    /// Uses the emacs or vi keymap, see [PromptState::keymap]
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_keys(&mut self, key: Key) -> bool {
+        match self.keymap {
+            PromptKeymap::Emacs => self.handle_emacs_keys(key),
+            PromptKeymap::Vi => self.handle_vi_keys(key),
+        }
+    }
+    /// Handle key events with the emacs keymap
+    /// Uses most of the default emacs keymaps! (Such as `Ctrl+U` to kill to the line start and `Ctrl+Y` to yank it back)
+    /// `Shift` with movement keys selects text, `Up`/`Down` walk the history and `Ctrl+R` searches it
+    #[cfg(feature = "backend-event")]
+    fn handle_emacs_keys(&mut self, key: Key) -> bool {
+        use self::PromptAction as Action;
+        use crate::event::{KeyCode as C, KeyMod};
+
+        let ctrl_shift = KeyMod::CTRL | KeyMod::SHIFT;
+
+        // Keys typed during the history search edit the query, other keys accept the match
+        if self.search.is_some() {
+            match key {
+                Key(KeyMod::CTRL, C::Char('r')) => return self.search_history(),
+                Key(KeyMod::CTRL, C::Char('g')) | Key(_, C::Esc) => return self.cancel_search(),
+                Key(_, C::Backspace) => return self.search_pop(),
+                Key(KeyMod::NONE | KeyMod::SHIFT, C::Char(char)) => return self.search_push(char),
+                _ => {
+                    self.accept_search();
+                },
+            }
+        }
+
+        match key {
+            Key(KeyMod::NONE, C::Tab) if self.completion.is_some() => self.action(Action::CompleteNext),
+            Key(KeyMod::SHIFT, C::Tab) |
+            Key(_, C::BackTab) if self.completion.is_some() => self.action(Action::CompletePrev),
+            Key(KeyMod::NONE, C::Right | C::End) |
+            Key(KeyMod::CTRL, C::Char('f' | 'e')) if self.suggestion().is_some() => self.action(Action::AcceptSuggestion),
+
+            Key(m, C::Right) if m == ctrl_shift => self.action(Action::SelectNextWord),
+            Key(m, C::Left) if m == ctrl_shift => self.action(Action::SelectPrevWord),
+            Key(KeyMod::SHIFT, C::Left) => self.action(Action::SelectLeft(1)),
+            Key(KeyMod::SHIFT, C::Right) => self.action(Action::SelectRight(1)),
+            Key(KeyMod::SHIFT, C::Home) => self.action(Action::SelectStart),
+            Key(KeyMod::SHIFT, C::End) => self.action(Action::SelectEnd),
+            Key(KeyMod::CTRL, C::Char(' ' | '@')) => self.action(Action::SetMark),
+            Key(KeyMod::CTRL, C::Char('g')) => self.action(Action::ClearSelection),
+
+            Key(KeyMod::CTRL, C::Right) => self.action(Action::MoveNextWord),
+            Key(KeyMod::CTRL, C::Left) => self.action(Action::MovePrevWord),
+            Key(KeyMod::ALT, C::Char('f')) => self.action(Action::MoveNextWord),
+            Key(KeyMod::ALT, C::Char('b')) => self.action(Action::MovePrevWord),
+            Key(_, C::Left) => self.action(Action::MoveLeft(1)),
+            Key(_, C::Right) => self.action(Action::MoveRight(1)),
+            Key(KeyMod::CTRL, C::Char('b')) => self.action(Action::MoveLeft(1)),
+            Key(KeyMod::CTRL, C::Char('f')) => self.action(Action::MoveRight(1)),
+            Key(KeyMod::CTRL, C::Char('a')) => self.action(Action::MoveStart),
+            Key(KeyMod::CTRL, C::Char('e')) => self.action(Action::MoveEnd),
+            Key(_, C::Home) => self.action(Action::MoveStart),
+            Key(_, C::End) => self.action(Action::MoveEnd),
+
+            Key(KeyMod::CTRL, C::Char('w')) => self.action(Action::DeletePrevWord),
+            // Same as Ctrl + Backspace
+            Key(KeyMod::CTRL, C::Char('h')) => self.action(Action::DeletePrevWord),
+            Key(KeyMod::CTRL, C::Char('u')) => self.action(Action::DeleteToStart),
+            Key(KeyMod::CTRL, C::Char('k')) => self.action(Action::DeleteToEnd),
+            Key(_, C::Backspace) => self.action(Action::DeleteLeft(1)),
+            Key(_, C::Delete) => self.action(Action::DeleteRight(1)),
+            Key(_, C::Up) => self.action(Action::HistoryPrefixPrev),
+            Key(_, C::Down) => self.action(Action::HistoryPrefixNext),
+            Key(KeyMod::CTRL, C::Char('p')) => self.action(Action::HistoryPrev),
+            Key(KeyMod::CTRL, C::Char('n')) => self.action(Action::HistoryNext),
+            Key(KeyMod::CTRL, C::Char('r')) => self.action(Action::SearchHistory),
+
+            Key(KeyMod::CTRL, C::Char('y')) => self.action(Action::Yank),
+            Key(KeyMod::ALT, C::Char('y')) => self.action(Action::YankPop),
+
+            // Terminals send Ctrl+_ and Ctrl+/ as Ctrl+7
+            Key(KeyMod::CTRL, C::Char('_' | '/' | '7' | 'z')) => self.action(Action::Undo),
+            key if key.normalized() == Key(KeyMod::CTRL, C::Char('Z')) => self.action(Action::Redo),
+
+            Key(_, C::Char(char)) => self.action(Action::Char(char)),
+
+            _ => false,
+        }
+    }
+
+    /// Send a message to the state
+    /// Returns whether state has been updated or not
+    ///
+    /// # Notes
+    ///
+    /// - changes made by one action are undone at once, consecutive [PromptAction::Char] are undone at once too
+    /// - consecutive kills are joined into one kill ring entry
+    pub fn action(&mut self, msg: PromptAction) -> bool {
+        if msg != PromptAction::SearchHistory {
+            self.accept_search();
+        }
+
+        if !matches!(msg, PromptAction::CompleteNext | PromptAction::CompletePrev | PromptAction::AcceptSuggestion) {
+            self.completion = None;
+        }
+
+        let join_kill = self.last == LastAction::Kill;
+        if msg != PromptAction::YankPop {
+            self.last = LastAction::Other;
+        }
+
+        self.typing = matches!(msg, PromptAction::Char(_));
+        if !self.typing {
+            if let Some(step) = self.undo.back_mut() {
+                step.typing = false;
+            }
+        }
+
+        self.in_action = true;
+        self.step_open = false;
+        let updated = self.apply_action(msg, join_kill);
+        self.in_action = false;
+        self.typing = false;
+
+        updated
+    }
+    fn apply_action(&mut self, msg: PromptAction, join_kill: bool) -> bool {
+        use self::PromptAction as Msg;
+
+        // Typing replaces the selection, deleting deletes it, killing kills it
+        let selection = self.selection();
+        match (&msg, selection) {
+            (Msg::Char(_) | Msg::String(_) | Msg::Yank, Some(_)) => {
+                self.delete_selection();
+            },
+            (Msg::DeleteLeft(_) | Msg::DeleteRight(_), Some(_)) => return self.delete_selection(),
+            (msg, Some((start, end))) if msg.kills() => return self.kill(start..end, false, join_kill),
+            (msg, _) if msg.selects() || self.mark => (),
+            _ => self.clear_selection(),
+        }
+
+        let cur = self.cursor;
+
+        match msg {
+            Msg::Char(char) => self.push_char(cur, char),
+            Msg::String(s) => self.push_string(cur, s),
+
+            Msg::DeleteLeft(n) => self.delete_left(cur, n),
+            Msg::DeleteRight(n) => self.delete_right(cur, n),
+            Msg::DeleteNextWord => {
+                let w = self.get_next_word_width(cur);
+                self.kill(cur..cur + w, false, join_kill)
+            },
+            Msg::DeletePrevWord => {
+                let w = self.get_prev_word_width(cur);
+                self.kill(cur - w..cur, true, join_kill)
+            },
+            Msg::DeleteToStart => self.kill(0..cur, true, join_kill),
+            Msg::DeleteToEnd => self.kill(cur..self.len(), false, join_kill),
+            Msg::Clear => self.clear(),
+
+            Msg::MoveLeft(n) => self.move_left(n),
+            Msg::MoveRight(n) => self.move_right(n),
+            Msg::MoveNextWord => self.move_next_word(),
+            Msg::MovePrevWord => self.move_prev_word(),
+            Msg::MoveStart => self.move_start(),
+            Msg::MoveEnd => self.move_end(),
+            Msg::MoveTo(pos) => self.move_to(pos),
+
+            Msg::SelectLeft(n) => self.extend_selection().move_left(n),
+            Msg::SelectRight(n) => self.extend_selection().move_right(n),
+            Msg::SelectNextWord => self.extend_selection().move_next_word(),
+            Msg::SelectPrevWord => self.extend_selection().move_prev_word(),
+            Msg::SelectStart => self.extend_selection().move_start(),
+            Msg::SelectEnd => self.extend_selection().move_end(),
+            Msg::SetMark => self.set_mark(),
+            Msg::ClearSelection => {
+                self.clear_selection();
+                true
+            },
+
+            Msg::Yank => self.yank(),
+            Msg::YankPop => self.yank_pop(),
+
+            Msg::Undo => self.undo(),
+            Msg::Redo => self.redo(),
+
+            Msg::HistoryPrev => self.walk_history(true, false),
+            Msg::HistoryNext => self.walk_history(false, false),
+            Msg::HistoryPrefixPrev => self.walk_history(true, true),
+            Msg::HistoryPrefixNext => self.walk_history(false, true),
+            Msg::SearchHistory => self.search_history(),
+
+            Msg::CompleteNext => self.cycle_completion(true),
+            Msg::CompletePrev => self.cycle_completion(false),
+            Msg::AcceptSuggestion => self.accept_suggestion(),
+        }
+    }
+
+    // Editing
+
+    /// Replace graphemes in `range` with `text`
+    fn edit(&mut self, range: Range<usize>, text: &str) -> bool {
+        let len = self.len();
+        let start = range.start.min(len);
+        let end = range.end.clamp(start, len);
+
+        if start == end && text.is_empty() {
+            return true;
+        }
+
+        let bytes = self.byte_at(start)..self.byte_at(end);
+        let text_end = bytes.start + text.len();
+        let cursor_before = self.cursor;
+        let change = Change {
+            start: bytes.start,
+            removed: self.value[bytes.clone()].to_string(),
+            inserted: text.to_string(),
+        };
+
+        self.replace_bytes(bytes, text);
+
+        // Keep the cursor on the same text
+        if (start..=end).contains(&self.cursor) {
+            self.cursor = self.graphemes.partition_point(|g| g.start < text_end);
+        } else if self.cursor > end {
+            self.cursor = (self.cursor + self.len()).saturating_sub(len);
+        }
+        self.clear_selection();
+        self.history_index = None;
+        self.completion = None;
+
+        self.record(change, cursor_before);
+        true
+    }
+    /// Replace `bytes` of the value with `text`
+    /// Only the graphemes around the edit are segmented again, so editing a large value stays cheap
+    fn replace_bytes(&mut self, bytes: Range<usize>, text: &str) {
+        // Neighbours are segmented too, because the text can join them (for example combining marks)
+        let win_start = self.graphemes.partition_point(|g| g.start <= bytes.start).saturating_sub(2);
+        let win_end = (self.graphemes.partition_point(|g| g.start < bytes.end) + 1).min(self.len());
+        let win_byte_start = self.byte_at(win_start);
+        let shift = |start: usize| start + text.len() - bytes.len();
+        let new_win_byte_end = shift(self.byte_at(win_end));
+
+        self.value.replace_range(bytes.clone(), text);
+
+        // Boundaries after the edit can move too (for example pairs of regional indicators),
+        // keep segmenting until a new boundary lines up with an old one
+        let mut segmented = vec![];
+        let mut old = win_end;
+        let mut end = self.len();
+        for g in Grapheme::segment(&self.value[win_byte_start..], win_byte_start) {
+            while old < self.len() && shift(self.graphemes[old].start) < g.start {
+                old += 1;
+            }
+            if g.start >= new_win_byte_end && old < self.len() && shift(self.graphemes[old].start) == g.start {
+                end = old;
+                break;
+            }
+            segmented.push(g);
+        }
+
+        let old_width: usize = self.graphemes[win_start..end].iter().map(|g| g.width).sum();
+        let new_width: usize = segmented.iter().map(|g| g.width).sum();
+        self.width = self.width - old_width + new_width;
+
+        for g in &mut self.graphemes[end..] {
+            g.start = shift(g.start);
+        }
+        self.graphemes.splice(win_start..end, segmented);
+        self.validate();
+    }
+    /// Replace `bytes` of the value with `text`, see [PromptState::edit]
+    fn edit_bytes(&mut self, bytes: Range<usize>, text: &str) -> bool {
+        let start = self.index_at_byte(bytes.start);
+        let end = self.index_at_byte(bytes.end);
+        self.edit(start..end, text)
+    }
+    /// Index of the grapheme that starts at or after `byte`
+    fn index_at_byte(&self, byte: usize) -> usize {
+        self.graphemes.partition_point(|g| g.start < byte)
+    }
+    /// Byte offset of the grapheme at `index`
+    fn byte_at(&self, index: usize) -> usize {
+        self.graphemes
+            .get(index)
+            .map_or(self.value.len(), |g| g.start)
+    }
+
+    // Pushing
+
+    /// Insert a char at `pos`
+    /// Returns `false` if the char is rejected, see [PromptState::filter]
+    pub fn push_char(&mut self, pos: usize, char: char) -> bool {
+        self.push_string(pos, char)
+    }
+    /// Insert a string at `pos`
+    /// Rejected graphemes are dropped, see [PromptState::filter]
+    /// Returns `false` if nothing is inserted
+    pub fn push_string<S: ToString>(&mut self, pos: usize, s: S) -> bool {
+        let text = self.filter_text(pos..pos, &s.to_string());
+        if text.is_empty() {
+            return false;
+        }
+
+        self.edit(pos..pos, &text)
+    }
+
+    // Filtering
+
+    /// Returns `text` without graphemes rejected by the filters or beyond the max length and width,
+    /// when it replaces graphemes in `range`
+    fn filter_text(&self, range: Range<usize>, text: &str) -> String {
+        let start = range.start.min(self.len());
+        let end = range.end.clamp(start, self.len());
+
+        let mut len = self.len() - (end - start);
+        let mut width = self.width - self.graphemes[start..end].iter().map(|g| g.width).sum::<usize>();
+        let mut filtered = String::new();
+
+        for g in text.graphemes(true) {
+            if !self.filters.iter().all(|f| f.0.accept(g)) {
+                continue;
+            }
+
+            let g_width = g.width();
+            if self.max_len.is_some_and(|max| len + 1 > max)
+            || self.max_width.is_some_and(|max| width + g_width > max) {
+                break;
+            }
+
+            len += 1;
+            width += g_width;
+            filtered.push_str(g);
+        }
+
+        filtered
+    }
+    /// Remove all input filters
+    pub fn clear_filters(&mut self) {
+        self.filters.clear();
+    }
+
+    // Validation
+
+    /// Set value validator and validate the value
+    pub fn set_validator<V: Validator + Send + Sync + 'static>(&mut self, validator: V) {
+        self.validator = Some(filter::Shared(Arc::new(validator)));
+        self.validate();
+    }
+    pub fn clear_validator(&mut self) {
+        self.validator = None;
+        self.error = None;
+    }
+    /// Validate the value again
+    /// Returns whether the value is valid
+    pub fn validate(&mut self) -> bool {
+        self.error = self.validator
+            .as_ref()
+            .and_then(|v| v.0.validate(&self.value).err());
+
+        self.error.is_none()
+    }
+    // Highlighting
+
+    pub fn set_highlighter<H: Highlighter + Send + Sync + 'static>(&mut self, highlighter: H) {
+        self.highlighter = Some(filter::Shared(Arc::new(highlighter)));
+    }
+    pub fn clear_highlighter(&mut self) {
+        self.highlighter = None;
+    }
+    /// Styled pieces of the value
+    /// Returns no spans if there is no highlighter
+    pub fn highlight(&self) -> Vec<Span<'_>> {
+        self.highlighter
+            .as_ref()
+            .map(|h| h.0.highlight(&self.value))
+            .unwrap_or_default()
+    }
+
+    /// Validation error message
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    // Deleting
+
+    pub fn delete_left(&mut self, pos: usize, amount: usize) -> bool {
+        self.edit(pos.saturating_sub(amount)..pos, "")
+    }
+    pub fn delete_right(&mut self, pos: usize, amount: usize) -> bool {
+        self.edit(pos..pos.saturating_add(amount), "")
+    }
+    pub fn delete_next_word(&mut self, pos: usize) -> bool {
+        let w = self.get_next_word_width(pos);
+        self.delete_right(pos, w)
+    }
+    pub fn delete_prev_word(&mut self, pos: usize) -> bool {
+        let w = self.get_prev_word_width(pos);
+        self.delete_left(pos, w)
+    }
+    pub fn delete_to_start(&mut self, pos: usize) -> bool {
+        self.edit(0..pos, "");
+        self.move_start()
+    }
+    pub fn delete_to_end(&mut self, pos: usize) -> bool {
+        self.edit(pos..self.len(), "");
+        self.move_end()
+    }
+    pub fn clear(&mut self) -> bool {
+        self.edit(0..self.len(), "");
+        self.move_start()
+    }
+
+    // Undo
+
+    /// Add a change to the undo history
+    fn record(&mut self, change: Change, cursor_before: usize) {
+        self.redo.clear();
+
+        if let Some(step) = self.undo.back_mut() {
+            let last = step.changes.last_mut();
+            let open = self.in_action && self.step_open;
+
+            match last {
+                // Typed text continues the last change
+                Some(last) if (open || self.typing && step.typing)
+                    && change.removed.is_empty()
+                    && last.start + last.inserted.len() == change.start =>
+                {
+                    last.inserted.push_str(&change.inserted);
+                    step.cursor_after = self.cursor;
+                    return;
+                },
+                _ if open => {
+                    step.changes.push(change);
+                    step.cursor_after = self.cursor;
+                    return;
+                },
+                _ => ()
+            }
+        }
+
+        if self.undo_depth == 0 {
+            return;
+        }
+
+        self.undo.push_back(UndoStep {
+            changes: vec![change],
+            cursor_before,
+            cursor_after: self.cursor,
+            typing: self.typing,
+        });
+        self.step_open = true;
+
+        while self.undo.len() > self.undo_depth {
+            self.undo.pop_front();
+        }
+    }
+    /// Undo the last change
+    /// Returns `false` if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let Some(mut step) = self.undo.pop_back() else {
+            return false;
+        };
+
+        for change in step.changes.iter().rev() {
+            self.replace_bytes(change.start..change.start + change.inserted.len(), &change.removed);
+        }
+
+        step.typing = false;
+        self.clear_selection();
+        self.move_to(step.cursor_before);
+        self.redo.push(step);
+        true
+    }
+    /// Redo the last undone change
+    /// Returns `false` if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+
+        for change in &step.changes {
+            self.replace_bytes(change.start..change.start + change.removed.len(), &change.inserted);
+        }
+
+        self.clear_selection();
+        self.move_to(step.cursor_after);
+        self.undo.push_back(step);
+        true
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    /// Forget all undo and redo steps
+    pub fn clear_undo(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    // Killing
+
+    /// Delete graphemes in `range` and put them to the kill ring
+    /// When `join` is `true`, the text is added to the latest entry, before it if `backward`
+    fn kill(&mut self, range: Range<usize>, backward: bool, join: bool) -> bool {
+        let text = &self.value[self.byte_at(range.start)..self.byte_at(range.end)];
+
+        if !text.is_empty() {
+            match (join, backward) {
+                (false, _) => self.kill_ring.push(text),
+                (true, false) => self.kill_ring.append(text),
+                (true, true) => self.kill_ring.prepend(text),
+            }
+        }
+
+        self.last = LastAction::Kill;
+        self.edit(range, "")
+    }
+    /// Insert the current kill ring entry at the cursor
+    /// Returns `false` if the kill ring is empty
+    pub fn yank(&mut self) -> bool {
+        let Some(text) = self.kill_ring.current().map(str::to_string) else {
+            return false;
+        };
+
+        let start = self.cursor;
+        self.push_string(start, text);
+        self.last = LastAction::Yank(start, self.cursor);
+        true
+    }
+    /// Replace the just yanked text with the previous kill ring entry
+    /// Returns `false` if the last action wasn't a yank
+    pub fn yank_pop(&mut self) -> bool {
+        let LastAction::Yank(start, end) = self.last else {
+            return false;
+        };
+        if self.cursor != end || self.kill_ring.len() < 2 {
+            return false;
+        }
+
+        self.kill_ring.rotate();
+        let text = self.kill_ring.current().unwrap_or_default().to_string();
+
+        self.edit(start..end, &text);
+        self.last = LastAction::Yank(start, self.cursor);
+        true
+    }
+
+    // History
+
+    /// Add the value to the history and clear the prompt
+    /// Masked values are not added
+    /// Returns the value
+    pub fn submit(&mut self) -> String {
+        self.accept_search();
+        let value = self.value.clone();
+
+        if self.mask.is_none() {
+            self.history.push(&value);
+        }
+        self.load_line("");
+        self.history_index = None;
+
+        // The next line is typed from the insert mode
+        #[cfg(feature = "backend-event")]
+        {
+            self.vi.mode = ViMode::Insert;
+        }
+
+        value
+    }
+    /// Replace the value with a history line
+    /// Undo history is cleared, because it belongs to the previous line
+    fn load_line(&mut self, line: &str) {
+        self.replace_bytes(0..self.value.len(), line);
+        self.clear_undo();
+        self.clear_selection();
+        self.move_end();
+    }
+    /// Show the previous (`back`) or the next history entry
+    /// When `prefix` is `true`, only entries starting with the line being typed are shown
+    fn walk_history(&mut self, back: bool, prefix: bool) -> bool {
+        let index = match self.history_index {
+            Some(index) => index,
+            None if back => {
+                self.history_draft = self.value.clone();
+                self.history.len()
+            },
+            None => return false,
+        };
+
+        let prefix = if prefix { self.history_draft.as_str() } else { "" };
+        let matches = |e: &String| e.starts_with(prefix) && *e != self.value;
+        let entries = self.history.entries();
+
+        let found = if back {
+            entries[..index].iter().rposition(matches)
+        } else {
+            entries[index + 1..].iter().position(matches).map(|i| i + index + 1)
+        };
+
+        match found {
+            Some(i) => {
+                let line = entries[i].clone();
+                self.load_line(&line);
+                self.history_index = Some(i);
+                true
+            },
+            None if back => false,
+            None => {
+                let draft = std::mem::take(&mut self.history_draft);
+                self.load_line(&draft);
+                self.history_index = None;
+                true
+            },
+        }
+    }
+
+    /// Start the reverse incremental history search, or find the next older match if it's started
+    pub fn search_history(&mut self) -> bool {
+        match &self.search {
+            Some(search) => {
+                let before = search.found.unwrap_or(self.history.len());
+                self.find_in_history(before)
+            },
+            None => {
+                self.search = Some(HistorySearch {
+                    query: String::new(),
+                    found: None,
+                    failed: false,
+                    line: self.value.clone(),
+                    cursor: self.cursor,
+                });
+                true
+            },
+        }
+    }
+    /// Add a char to the search query
+    /// Returns `false` if the search is not started
+    pub fn search_push(&mut self, char: char) -> bool {
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+
+        search.query.push(char);
+        // The current match is checked again
+        let before = search.found.map_or(self.history.len(), |i| i + 1);
+        self.find_in_history(before)
+    }
+    /// Remove the last char of the search query
+    /// Returns `false` if the search is not started
+    pub fn search_pop(&mut self) -> bool {
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+
+        search.query.pop();
+        self.find_in_history(self.history.len())
+    }
+    /// Find the latest entry before `before` that contains the search query
+    fn find_in_history(&mut self, before: usize) -> bool {
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+        if search.query.is_empty() {
+            search.failed = false;
+            return true;
+        }
+
+        let Some(found) = self.history.search(&search.query, before) else {
+            search.failed = true;
+            return true;
+        };
+
+        search.found = Some(found);
+        search.failed = false;
+
+        let line = self.history.entries()[found].clone();
+        let byte = line.find(&search.query).unwrap_or_default();
+
+        self.load_line(&line);
+        self.cursor = self.graphemes.partition_point(|g| g.start < byte);
+        true
+    }
+    /// Stop the search, keeping the matched entry
+    /// Returns `false` if the search is not started
+    pub fn accept_search(&mut self) -> bool {
+        let Some(search) = self.search.take() else {
+            return false;
+        };
+
+        // Walking the history continues from the match
+        if search.found.is_some() {
+            self.history_index = search.found;
+            self.history_draft = search.line;
+        }
+        true
+    }
+    /// Stop the search, restoring the value from before it
+    /// Returns `false` if the search is not started
+    pub fn cancel_search(&mut self) -> bool {
+        let Some(search) = self.search.take() else {
+            return false;
+        };
+
+        self.load_line(&search.line);
+        self.move_to(search.cursor)
+    }
+    /// Search query, `None` if the search is not started
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.as_ref().map(|s| s.query.as_str())
+    }
+    /// Whether nothing is found with the search query
+    pub fn is_search_failed(&self) -> bool {
+        self.search.as_ref().is_some_and(|s| s.failed)
+    }
+
+    // Completion
+
+    /// Query `completer` for candidates at the cursor
+    /// Call it after the value changes, for example when [PromptState::handle_event] returns `true`
+    /// Does nothing while cycling through candidates
+    /// Returns whether there are candidates
+    pub fn complete<C: Completer + ?Sized>(&mut self, completer: &C) -> bool {
+        if self.completion.as_ref().is_some_and(|c| c.selected.is_some()) {
+            return true;
+        }
+
+        let candidates = completer.complete(&self.value, self.byte_at(self.cursor));
+        self.completion = (!candidates.is_empty()).then_some(Completion {
+            candidates,
+            selected: None,
+            applied: None,
+        });
+
+        self.completion.is_some()
+    }
+    /// Replace the completed text with the next (`forward`) or the previous candidate
+    /// After the last candidate the typed text is restored.
+    /// A single candidate is applied right away
+    fn cycle_completion(&mut self, forward: bool) -> bool {
+        let Some(mut completion) = self.completion.take() else {
+            return false;
+        };
+
+        let len = completion.candidates.len();
+        let selected = match (completion.selected, forward) {
+            (None, true) => Some(0),
+            (None, false) => Some(len - 1),
+            (Some(i), true) => (i + 1 < len).then_some(i + 1),
+            (Some(i), false) => i.checked_sub(1),
+        };
+
+        if let Some((bytes, replaced)) = completion.applied.take() {
+            self.edit_bytes(bytes, &replaced);
+        }
+        if let Some(candidate) = selected.map(|i| &completion.candidates[i]) {
+            if let Some(replaced) = self.value.get(candidate.range.clone()).map(str::to_string) {
+                let range = &candidate.range;
+                let text = self.filter_text(
+                    self.index_at_byte(range.start)..self.index_at_byte(range.end),
+                    &candidate.text
+                );
+
+                self.edit_bytes(range.clone(), &text);
+                completion.applied = Some((range.start..range.start + text.len(), replaced));
+            }
+        }
+
+        completion.selected = selected;
+        self.completion = (len > 1).then_some(completion);
+        true
+    }
+    /// Inline suggestion, the rest of the first candidate
+    /// Only shown when the cursor is at the end of the value and candidates are not cycled through
+    pub fn suggestion(&self) -> Option<&str> {
+        let completion = self.completion.as_ref()?;
+        let candidate = completion.candidates.first()?;
+
+        if completion.selected.is_some()
+        || self.mask.is_some()
+        || self.cursor != self.len()
+        || candidate.range.end != self.value.len() {
+            return None;
+        }
+
+        let typed = self.value.get(candidate.range.clone())?;
+        candidate.text
+            .strip_prefix(typed)
+            .filter(|rest| !rest.is_empty())
+    }
+    /// Insert the inline suggestion
+    /// Returns `false` if there is no suggestion
+    pub fn accept_suggestion(&mut self) -> bool {
+        let Some(rest) = self.suggestion().map(str::to_string) else {
+            return false;
+        };
+
+        self.push_string(self.cursor, rest)
+    }
+    /// Completion candidates
+    pub fn candidates(&self) -> &[Candidate] {
+        self.completion
+            .as_ref()
+            .map_or(&[], |c| &c.candidates)
+    }
+    /// Index of the applied candidate when cycling through them
+    pub fn selected_candidate(&self) -> Option<usize> {
+        self.completion.as_ref()?.selected
+    }
+    pub fn clear_completion(&mut self) {
+        self.completion = None;
+    }
+
+    // Selection
+
+    /// Select from `anchor` to `cursor`
+    pub fn select(&mut self, anchor: usize, cursor: usize) -> bool {
+        self.anchor = Some(anchor.min(self.len()));
+        self.move_to(cursor)
+    }
+    pub fn select_all(&mut self) -> bool {
+        self.select(0, self.len())
+    }
+    /// Clear the selection and deactivate the mark
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+        self.mark = false;
+    }
+    /// Set the mark at the cursor, or deactivate it if it's set
+    pub fn set_mark(&mut self) -> bool {
+        if self.mark {
+            self.clear_selection();
+        } else {
+            self.anchor = Some(self.cursor);
+            self.mark = true;
+        }
+        true
+    }
+    /// Whether the mark is set
+    pub fn is_mark_set(&self) -> bool {
+        self.mark
+    }
+    /// Start the selection at the cursor if there is none
+    fn extend_selection(&mut self) -> &mut Self {
+        self.anchor.get_or_insert(self.cursor);
+        self
+    }
+    /// Delete the selected text
+    /// Returns `false` if nothing is selected
+    pub fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            return false;
+        };
+
+        self.edit(start..end, "")
+    }
+    /// Selection range `(start, end)`
+    /// Returns `None` if nothing is selected
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        let cur = self.cursor;
+
+        // The vi visual selection includes the grapheme under the cursor
+        #[cfg(feature = "backend-event")]
+        if self.is_vi_visual() {
+            return Some((anchor.min(cur), (anchor.max(cur) + 1).min(self.len())));
+        }
+
+        (anchor != cur).then(|| (anchor.min(cur), anchor.max(cur)))
+    }
+    /// Selected text
+    pub fn selected_text(&self) -> Option<&str> {
+        let (start, end) = self.selection()?;
+        self.value.get(self.byte_at(start)..self.byte_at(end))
+    }
+
+    // Move cursor
+
+    pub fn move_start(&mut self) -> bool {
+        self.move_to(0)
+    }
+    pub fn move_end(&mut self) -> bool {
+        self.move_to(self.len())
+    }
+    pub fn move_next_word(&mut self) -> bool {
+        let w = self.get_next_word_width(self.cursor);
+        self.move_right(w)
+    }
+    pub fn move_prev_word(&mut self) -> bool {
+        let w = self.get_prev_word_width(self.cursor);
+        self.move_left(w)
+    }
+    pub fn move_left(&mut self, amount: usize) -> bool {
+        self.move_to(self.cursor.saturating_sub(amount))
+    }
+    pub fn move_right(&mut self, amount: usize) -> bool {
+        self.move_to(self.cursor.saturating_add(amount))
+    }
+    pub fn move_to(&mut self, pos: usize) -> bool {
+        self.cursor = pos.min(self.len());
+        true
+    }
+
+    // Get
+
+    /// Grapheme at `pos`
+    pub fn char_at(&self, pos: usize) -> Option<&str> {
+        if pos >= self.len() {
+            return None;
+        }
+
+        self.value.get(self.byte_at(pos)..self.byte_at(pos + 1))
+    }
+    /// Length of the word in graphemes, including separators before it
+    /// Words are split by [PromptState::word_mode]
+    pub fn get_word_width<I>(&self, range: I) -> usize
+    where
+        I: Iterator,
+        I::Item: Into<usize>,
+    {
+        let ids = self.word_ids();
+        let mut word = None;
+        let mut width = 0usize;
+
+        for i in range {
+            match ids.get(i.into()).copied().flatten() {
+                // Separators before the word
+                None if word.is_none() => (),
+                None => break,
+                // Adjacent words, like CJK ideographs
+                Some(id) if word.is_some_and(|w| w != id) => break,
+                id => word = id,
+            }
+            width += 1;
+        }
+
+        width
+    }
+    /// Word index of each grapheme, `None` for separators
+    fn word_ids(&self) -> Vec<Option<usize>> {
+        let mut ids = vec![None; self.len()];
+
+        for (id, bytes) in self.word_mode.words(&self.value).into_iter().enumerate() {
+            let start = self.index_at_byte(bytes.start);
+            let end = self.index_at_byte(bytes.end);
+            ids[start..end].fill(Some(id));
+        }
+
+        ids
+    }
+    pub fn get_next_word_width(&self, pos: usize) -> usize {
+        self.get_word_width(pos..self.len())
+    }
+    pub fn get_prev_word_width(&self, pos: usize) -> usize {
+        self.get_word_width((0..pos).rev())
+    }
+
+    /// Display width of a grapheme, taking the mask into account
+    fn display_width(&self, g: &Grapheme) -> usize {
+        match self.mask {
+            None => g.width,
+            Some(PromptMask::Char(char)) => char.width().unwrap_or_default(),
+            Some(PromptMask::Hidden) => 0,
+        }
+    }
+    /// Display column of the grapheme at `index`
+    pub fn col_of(&self, index: usize) -> usize {
+        self.graphemes
+            .iter()
+            .take(index)
+            .map(|g| self.display_width(g))
+            .sum()
+    }
+    /// Grapheme index at the display column `col`
+    /// The right half of a wide grapheme belongs to the position after it
+    pub fn index_at_col(&self, col: usize) -> usize {
+        let mut cur_col = 0usize;
+
+        for (i, g) in self.graphemes.iter().enumerate() {
+            let width = self.display_width(g);
+            if col < cur_col + width.div_ceil(2) {
+                return i;
+            }
+            cur_col += width;
+        }
+
+        self.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+    /// Value length in graphemes
+    pub fn len(&self) -> usize {
+        self.graphemes.len()
+    }
+
+    /// Prompt value
+    pub fn value(&self) -> &String {
+        &self.value
+    }
+    /// Replace the value
+    /// The change can be undone
+    pub fn set_value(&mut self, value: String) {
+        let cursor = self.cursor;
+        self.edit(0..self.len(), &value);
+        self.move_to(cursor);
+    }
+    /// Cursor position in graphemes
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    /// Cursor display column
+    pub fn cursor_pos(&self) -> usize {
+        self.col_of(self.cursor)
+    }
+    /// Value display width
+    pub fn width(&self) -> usize {
+        match self.mask {
+            None => self.width,
+            Some(_) => self.col_of(self.len()),
+        }
+    }
+    /// Horizontal scroll of the value drawn in `width` columns
+    /// Keeps a quarter of the width free after the cursor
+    pub fn scroll(&self, width: u16) -> u16 {
+        let cur_pos = self.cursor_pos() as u16;
+        cur_pos.saturating_sub(width - width / 4)
+    }
+}
+impl Default for PromptState {
+    fn default() -> Self {
+        Self {
+            paste_newlines: PasteNewlines::default(),
+            mask: None,
+            max_len: None,
+            max_width: None,
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            word_mode: WordMode::default(),
+            kill_ring: KillRing::new(),
+            history: History::new(),
+            #[cfg(feature = "backend-event")]
+            keymap: PromptKeymap::default(),
+            value: String::new(),
+            graphemes: vec![],
+            cursor: 0,
+            width: 0,
+            anchor: None,
+            mark: false,
+            last: LastAction::Other,
+            dragging: false,
+            undo: VecDeque::new(),
+            redo: vec![],
+            in_action: false,
+            step_open: false,
+            typing: false,
+            history_index: None,
+            history_draft: String::new(),
+            search: None,
+            completion: None,
+            filters: vec![],
+            validator: None,
+            highlighter: None,
+            error: None,
+            #[cfg(feature = "backend-event")]
+            vi: vi::Vi::default(),
+        }
+    }
+}
+
+/// Prompt widget
+/// The value is drawn with the highlighter of the state, see [PromptState::highlighter]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompt<'a> {
+    pub state: &'a PromptState,
+    pub style: Style,
+    pub cursor_style: Style,
+    /// Cursor style in the vi normal and visual modes
+    #[cfg(feature = "backend-event")]
+    pub normal_cursor_style: Style,
+    pub selection_style: Style,
+    pub search_style: Style,
+    pub suggestion_style: Style,
+    pub dropdown_style: Style,
+    pub dropdown_selected_style: Style,
+    /// Max number of candidates in the dropdown, the dropdown is hidden if `None`
+    pub dropdown: Option<u16>,
+    /// Text shown when the value is empty
+    pub placeholder: Option<String>,
+    pub placeholder_style: Style,
+    /// Style of the value and borders when the value is invalid
+    pub error_style: Style,
+    /// Whether to draw the validation error under the prompt
+    pub show_error: bool,
+    pub focused: bool,
+    pub borders: Option<Borders>
+}
+impl<'a> Prompt<'a> {
+    pub fn new(state: &'a PromptState) -> Self {
+        Self {
+            state,
+            style: Style::default(),
+            cursor_style: Style::new(Color::Black, Color::LightGray),
+            #[cfg(feature = "backend-event")]
+            normal_cursor_style: Style::new(Color::Black, Color::Green),
+            selection_style: Style::new(Color::Black, Color::Blue),
+            search_style: Color::Gray.into(),
+            suggestion_style: Color::LightBlack.into(),
+            dropdown_style: Style::new(Color::LightGray, Color::LightBlack),
+            dropdown_selected_style: Style::new(Color::Black, Color::Blue),
+            dropdown: None,
+            placeholder: None,
+            placeholder_style: Color::LightBlack.into(),
+            error_style: Color::Red.into(),
+            show_error: false,
+            focused: true,
+            borders: None,
+        }
+    }
+
+    //
+
+    /// Set prompt text style
+    pub fn style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.style = style.into();
+        self
+    }
+    /// Set prompt cursor style
+    pub fn cursor_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.cursor_style = style.into();
+        self
+    }
+    /// Set cursor style in the vi normal and visual modes
+    #[cfg(feature = "backend-event")]
+    pub fn normal_cursor_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.normal_cursor_style = style.into();
+        self
+    }
+    /// Set selected text style
+    pub fn selection_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.selection_style = style.into();
+        self
+    }
+    /// Set history search label style
+    pub fn search_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.search_style = style.into();
+        self
+    }
+    /// Set inline suggestion style
+    pub fn suggestion_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.suggestion_style = style.into();
+        self
+    }
+    /// Set completion dropdown style
+    pub fn dropdown_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.dropdown_style = style.into();
+        self
+    }
+    /// Set style of the selected candidate in the dropdown
+    pub fn dropdown_selected_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.dropdown_selected_style = style.into();
+        self
+    }
+    /// Show completion candidates in a dropdown under the prompt
+    /// Shows up to `max_items` candidates at once
+    pub fn dropdown(mut self, max_items: u16) -> Self {
+        self.dropdown = Some(max_items);
+        self
+    }
+    /// Set text shown when the value is empty
+    pub fn placeholder<S: ToString>(mut self, text: S) -> Self {
+        self.placeholder = Some(text.to_string());
+        self
+    }
+    /// Set placeholder text style
+    pub fn placeholder_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.placeholder_style = style.into();
+        self
+    }
+    /// Set style of the value and borders when the value is invalid
+    pub fn error_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.error_style = style.into();
+        self
+    }
+    /// Set whether to draw the validation error under the prompt
+    /// The rect returned by [Draw::draw] includes the error row while there is an error
+    /// Use [PromptState::error] to draw it elsewhere
+    pub fn show_error(mut self, value: bool) -> Self {
+        self.show_error = value;
+        self
+    }
+    /// Set whether the cursor is visible or not
+    pub fn focused(mut self, value: bool) -> Self {
+        self.focused = value;
+        self
+    }
+    /// Set border kind
+    pub fn border_kind(mut self, kind: BorderKind) -> Self {
+        if let Some(borders) = self.borders {
+            self.borders = Some(borders.kind(kind));
+        } else {
+            self.borders = Some(Borders::new(kind));
+        }
+
+        self
+    }
+    /// Set border style
+    pub fn border_style<S: Into<Style>>(mut self, style: S) -> Self {
+        if let Some(borders) = self.borders {
+            self.borders = Some(borders.style(style));
+        } else {
+            self.borders = Some(Borders::single().style(style));
+        }
+
+        self
+    }
+    /// Set a cell to fill the background
+    pub fn fill<C: Into<Cell>>(mut self, cell: C) -> Self {
+        if let Some(borders) = self.borders {
+            self.borders = Some(borders.fill(cell));
+        } else {
+            self.borders = Some(Borders::single().fill(cell));
+        }
+
+        self
+    }
+
+    /// Returns the rect the value is drawn in when the prompt is drawn in `rect`
+    /// Pass it to [PromptState::handle_event]
+    pub fn text_rect(&self, rect: Rect) -> Rect {
+        if self.borders.is_some() {
+            rect.with_height(3).margin(1)
+        } else {
+            rect.with_height(1)
+        }
+    }
+    /// Returns the rect of the completion dropdown when the prompt is drawn in `rect`
+    /// Returns `None` if the dropdown is hidden, shows no items or there are no candidates
+    pub fn dropdown_rect(&self, rect: Rect) -> Option<Rect> {
+        let max_items = self.dropdown.filter(|n| *n > 0)?;
+        let candidates = self.state.candidates();
+        if !self.focused || candidates.is_empty() {
+            return None;
+        }
+
+        let text_rect = self.text_rect(rect);
+        let height = (candidates.len() as u16).min(max_items);
+        let width = candidates
+            .iter()
+            .map(|c| c.label().width() as u16 + 2)
+            .max()
+            .unwrap_or_default()
+            .min(text_rect.width);
+        let y = if self.borders.is_some() { rect.y + 3 } else { rect.y + 1 };
+
+        Some(Rect::new(text_rect.x, y, width, height))
+    }
+}
+
+impl<'a> Draw for Prompt<'a> {
+    fn draw(self, buf: &mut Buffer, rect: Rect) -> Rect {
+        let is_borders = self.borders.is_some();
+        let borders_rect = self.text_rect(rect);
+        let text_rect = borders_rect;
+        let dropdown_rect = self.dropdown_rect(rect);
+        let error = self.state.error();
+        let style = match error {
+            Some(_) => self.style.set(self.error_style),
+            None => self.style
+        };
+
+        if let Some(borders) = self.borders {
+            let borders = match error {
+                Some(_) => borders.style(self.error_style),
+                None => borders
+            };
+            borders.draw(buf, rect.with_height(3));
+        }
+
+        // Draw the history search label before the value
+        let borders_rect = match self.state.search_query() {
+            Some(query) => {
+                let failed = if self.state.is_search_failed() { "failed " } else { "" };
+                let label = format!("({failed}reverse-i-search)`{query}': ");
+                let label_width = (label.width() as u16).min(borders_rect.width);
+
+                buf.set_clamped_string(borders_rect.pos(), 0, &label, self.search_style, label_width);
+                borders_rect.add_x(label_width).sub_width(label_width)
+            },
+            None => borders_rect
+        };
+
+        let cur_pos = self.state.cursor_pos() as u16;
+        let scroll = if self.focused {
+            self.state.scroll(borders_rect.width)
+        } else {
+            0
+        };
+        let (x, y) = (borders_rect.x, borders_rect.y);
+
+        // Draw placeholder
+        if let Some(placeholder) = self.placeholder.as_ref().filter(|_| self.state.is_empty()) {
+            buf.set_clamped_string((x, y), 0, placeholder, self.placeholder_style, borders_rect.width);
+        }
+
+        // Masked values are not highlighted, so colors don't reveal them
+        let spans = match self.state.mask {
+            None => self.state.highlight(),
+            Some(_) => vec![],
+        };
+        // End byte and style of each span
+        let mut span_ends = spans
+            .iter()
+            .scan(0usize, |end, span| {
+                *end += span.len();
+                Some((*end, span.style))
+            })
+            .peekable();
+
+        // Draw text
+        let mut mask_buf = [0; 4];
+        let mut col = 0u16;
+        for (i, g) in self.state.graphemes.iter().enumerate() {
+            let width = self.state.display_width(g) as u16;
+            let start = col;
+            col = col.saturating_add(width);
+
+            while span_ends.next_if(|(end, _)| *end <= g.start).is_some() {}
+            let style = match span_ends.peek() {
+                Some((_, span_style)) => style.set(*span_style),
+                None => style,
+            };
+
+            if col <= scroll || width == 0 {
+                continue;
+            }
+            if col - scroll > borders_rect.width {
+                break;
+            }
+
+            let text = match self.state.mask {
+                Some(PromptMask::Char(char)) => Some(&*char.encode_utf8(&mut mask_buf)),
+                _ => self.state.char_at(i),
+            };
+
+            if start < scroll {
+                // Wide grapheme cut by the left edge
+                for cut_x in 0..col - scroll {
+                    buf.set((x + cut_x, y), Some(" "), style);
+                }
+            } else {
+                buf.set((x + start - scroll, y), text, style);
+            }
+        }
+
+        // Draw selection
+        if let Some((start, end)) = self.state.selection() {
+            let start = (self.state.col_of(start) as u16).max(scroll) - scroll;
+            let end = (self.state.col_of(end) as u16).saturating_sub(scroll).min(borders_rect.width);
+
+            for sel_x in start..end {
+                buf.set_style((x + sel_x, y), self.selection_style);
+            }
+        }
+
+        // Draw the inline suggestion after the value
+        if let Some(suggestion) = self.state.suggestion().filter(|_| self.focused) {
+            let start = (self.state.width() as u16).saturating_sub(scroll);
+            if start < borders_rect.width {
+                buf.set_clamped_string(
+                    (x + start, y),
+                    0,
+                    suggestion,
+                    self.suggestion_style,
+                    borders_rect.width - start
+                );
+            }
+        }
+
+        // Draw cursor
+        if self.focused {
+            let scrolled_pos = cur_pos.saturating_sub(scroll);
+            let cur_x = borders_rect.x.saturating_add(scrolled_pos);
+
+            #[cfg(feature = "backend-event")]
+            let cursor_style = match self.state.vi_mode() {
+                Some(ViMode::Normal | ViMode::Visual) => self.normal_cursor_style,
+                _ => self.cursor_style,
+            };
+            #[cfg(not(feature = "backend-event"))]
+            let cursor_style = self.cursor_style;
+
+            buf.set_style((cur_x, borders_rect.y), cursor_style);
+        }
+
+        let height = if is_borders { 3 } else { 1 };
+
+        // Draw the validation error under the prompt
+        let shown_error = error.filter(|_| self.show_error);
+        if let Some(error) = shown_error {
+            let error_y = rect.y.saturating_add(height);
+
+            buf.set_clamped_string((text_rect.x, error_y), 0, error, self.error_style, text_rect.width);
+        }
+
+        // Draw the dropdown over whatever is under the prompt
+        if let Some(dropdown_rect) = dropdown_rect {
+            let candidates = self.state.candidates();
+            let selected = self.state.selected_candidate();
+            let first = selected.map_or(0, |s| s.saturating_sub((dropdown_rect.height as usize).saturating_sub(1)));
+            let rows = candidates
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(dropdown_rect.height as usize);
+
+            for (row_y, (i, candidate)) in (dropdown_rect.y..).zip(rows) {
+                let style = if selected == Some(i) {
+                    self.dropdown_selected_style
+                } else {
+                    self.dropdown_style
+                };
+
+                for cell_x in 0..dropdown_rect.width {
+                    buf.set((dropdown_rect.x + cell_x, row_y), Some(" "), style);
+                }
+                buf.set_clamped_string(
+                    (dropdown_rect.x + 1, row_y),
+                    0,
+                    candidate.label(),
+                    style,
+                    dropdown_rect.width.saturating_sub(2)
+                );
+            }
+        }
+
+        match shown_error {
+            Some(_) => rect.with_height(height + 1),
+            None => rect.with_height(height)
+        }
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste_newlines() {
+        assert_eq!(PasteNewlines::Space.apply("a\r\nb\nc"), "a b c");
+        assert_eq!(PasteNewlines::Strip.apply("a\r\nb\rc"), "abc");
+        assert_eq!(PasteNewlines::FirstLine.apply("a\nb"), "a");
+        assert_eq!(PasteNewlines::Keep.apply("a\r\nb"), "a\nb");
+    }
+
+    #[cfg(feature = "backend-event")]
+    #[test]
+    fn paste_and_mouse() {
+        use crate::event::KeyMod;
+
+        let area = Rect::new(2, 1, 8, 1);
+        let mut state = PromptState::new("hello");
+
+        assert!(state.handle_event(&Event::Paste("\nw\norld".into()), area));
+        assert_eq!(state.value(), "hello w orld");
+
+        // Cursor is at 12, so the value is scrolled by 12 - 6 = 6
+        assert_eq!(state.scroll(area.width), 6);
+        state.handle_mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 1), area);
+        assert_eq!(state.cursor_pos(), 7);
+        assert_eq!(state.selection(), None);
+
+        state.move_start();
+        assert!(!state.handle_mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 0), area), "Outside of the area");
+        state.handle_mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 1), area);
+        state.handle_mouse(Mouse::Drag(KeyMod::NONE, MouseBtn::Left, 6, 1), area);
+        state.handle_mouse(Mouse::Up(KeyMod::NONE, MouseBtn::Left, 6, 1), area);
+        assert_eq!(state.selection(), Some((1, 4)));
+        assert_eq!(state.selected_text(), Some("ell"));
+
+        state.action(PromptAction::Char('E'));
+        assert_eq!(state.value(), "hEo w orld", "Typing replaces the selection");
+        assert_eq!(state.cursor_pos(), 2);
+    }
+
+    #[test]
+    fn graphemes() {
+        let mut state = PromptState::new("a界e\u{301}b");
+
+        assert_eq!(state.len(), 4);
+        assert_eq!(state.width(), 5);
+        assert_eq!(state.cursor(), 4);
+        assert_eq!(state.cursor_pos(), 5);
+
+        state.action(PromptAction::MoveLeft(1));
+        assert_eq!(state.cursor_pos(), 4);
+        state.action(PromptAction::DeleteLeft(1));
+        assert_eq!(state.value(), "a界b", "Combining mark is deleted with its letter");
+        state.action(PromptAction::DeleteLeft(1));
+        assert_eq!(state.value(), "ab");
+        assert_eq!(state.cursor_pos(), 1);
+
+        // Combining mark joins the previous letter
+        state.action(PromptAction::Char('\u{308}'));
+        assert_eq!(state.value(), "a\u{308}b");
+        assert_eq!((state.len(), state.cursor()), (2, 1));
+        assert_eq!(state.char_at(0), Some("a\u{308}"));
+
+        state.action(PromptAction::String("日本 ".into()));
+        assert_eq!(state.value(), "a\u{308}日本 b");
+        assert_eq!((state.cursor(), state.cursor_pos()), (4, 6));
+        assert_eq!(state.col_of(2), 3);
+        assert_eq!(state.index_at_col(3), 2);
+        assert_eq!(state.index_at_col(4), 3, "Right half of a wide char");
+
+        // Each ideograph is a word
+        state.action(PromptAction::DeletePrevWord);
+        assert_eq!(state.value(), "a\u{308}日b");
+        assert_eq!(state.width(), 4);
+    }
+
+    #[test]
+    fn grapheme_edits() {
+        fn assert_segmented(state: &PromptState) {
+            let fresh = PromptState::new(state.value());
+            assert_eq!(state.graphemes, fresh.graphemes, "{:?}", state.value());
+            assert_eq!(state.width(), fresh.width());
+        }
+
+        // Regional indicators pair up again after an edit
+        let mut state = PromptState::new("🇺🇸🇬🇧🇫🇷🇩🇪");
+        state.push_char(0, '\u{1F1EF}');
+        assert_segmented(&state);
+        assert_eq!(state.char_at(1), Some("🇸🇬"));
+        state.edit(0..1, "");
+        assert_segmented(&state);
+
+        // Random edits with pieces that join their neighbours
+        let pieces = ["\u{1F1EF}", "🇺🇸", "\u{301}", "e", "\u{200D}", "👨", "界", " ", "\r", "\n", "\u{1F3FB}"];
+        let mut seed = 0x2545_F491_4F6C_DD1Du64;
+        let mut random = |max: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % max as u64) as usize
+        };
+
+        let mut state = PromptState::new("🇺🇸🇬🇧a\u{301}👨\u{200D}👩 界\r\n");
+        for _ in 0..2000 {
+            let start = random(state.len() + 1);
+            let end = (start + random(3)).min(state.len());
+            let text: String = (0..random(3)).map(|_| pieces[random(pieces.len())]).collect();
+
+            state.edit(start..end, &text);
+            assert_segmented(&state);
+        }
+    }
+
+    #[test]
+    fn word_modes() {
+        let path = "cd ~/src/my-app/";
+
+        let mut state = PromptState::new(path);
+        state.action(PromptAction::DeletePrevWord);
+        assert_eq!(state.value(), "cd ~/src/my-");
+        state.action(PromptAction::MovePrevWord);
+        assert_eq!(state.cursor(), 9);
+
+        let mut state = PromptState::new(path).word_mode(WordMode::Path);
+        state.action(PromptAction::DeletePrevWord);
+        assert_eq!(state.value(), "cd ~/src/");
+        state.action(PromptAction::MoveStart);
+        state.action(PromptAction::MoveNextWord);
+        state.action(PromptAction::MoveNextWord);
+        assert_eq!(state.cursor(), 4);
+        state.action(PromptAction::DeleteNextWord);
+        assert_eq!(state.value(), "cd ~/");
+
+        let mut state = PromptState::new(path).word_mode(WordMode::Whitespace);
+        state.action(PromptAction::DeletePrevWord);
+        assert_eq!(state.value(), "cd ");
+    }
+
+    #[test]
+    fn highlight() {
+        fn flags(value: &str) -> Vec<Span<'_>> {
+            value
+                .split_inclusive(' ')
+                .map(|word| match word.starts_with('-') {
+                    true => Span::new(word, Color::Yellow),
+                    false => Span::from(word),
+                })
+                .collect()
+        }
+
+        let state = PromptState::new("ls -la ~").highlighter(flags);
+        let mut buf = Buffer::empty(10, 1);
+        Prompt::new(&state).style(Color::Red).draw(&mut buf, Rect::sized(10, 1));
+
+        let fg = |buf: &Buffer, x: u16| buf.get((x, 0)).unwrap().style.fg;
+        assert_eq!(fg(&buf, 0), Some(Color::Red));
+        assert_eq!(fg(&buf, 3), Some(Color::Yellow));
+        assert_eq!(fg(&buf, 7), Some(Color::Red));
+
+        let state = state.mask(PromptMask::Char('*'));
+        let mut buf = Buffer::empty(10, 1);
+        Prompt::new(&state).draw(&mut buf, Rect::sized(10, 1));
+        assert_ne!(fg(&buf, 3), Some(Color::Yellow), "Masked values are not highlighted");
+    }
+
+    #[test]
+    fn large_paste() {
+        let mut state = PromptState::new("[]");
+        let text = "ab界\u{301} ".repeat(50_000);
+
+        state.move_left(1);
+        state.action(PromptAction::String(text.clone()));
+        state.action(PromptAction::Char('!'));
+
+        assert_eq!(state.value(), &format!("[{text}!]"));
+        assert_eq!(state.len(), 200_003);
+        assert_eq!(state.cursor(), 200_002);
+        assert_eq!(state.width(), 250_003);
+
+        state.action(PromptAction::DeleteToStart);
+        assert_eq!(state.value(), "]");
+    }
+
+    #[test]
+    fn draw_wide() {
+        let state = PromptState::new("a界b");
+        let mut buf = Buffer::empty(4, 1);
+        Prompt::new(&state).focused(false).draw(&mut buf, Rect::new(0, 0, 3, 1));
+
+        assert_eq!(buf.get((1, 0)).and_then(|c| c.char.as_deref()), Some("界"));
+        assert_ne!(buf.get((3, 0)).and_then(|c| c.char.as_deref()), Some("b"), "Doesn't fit");
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut state = PromptState::new("hello");
+
+        for char in " big world".chars() {
+            state.action(PromptAction::Char(char));
+        }
+        state.action(PromptAction::DeletePrevWord);
+        assert_eq!(state.value(), "hello big ");
+
+        assert!(state.action(PromptAction::Undo));
+        assert_eq!(state.value(), "hello big world");
+        assert!(state.action(PromptAction::Undo));
+        assert_eq!(state.value(), "hello", "Typed chars are undone at once");
+        assert_eq!(state.cursor(), 5);
+        assert!(!state.action(PromptAction::Undo), "Nothing to undo");
+
+        assert!(state.action(PromptAction::Redo));
+        assert_eq!(state.value(), "hello big world");
+        assert_eq!(state.cursor(), 15);
+
+        // Moving the cursor starts a new step
+        state.action(PromptAction::MoveStart);
+        state.action(PromptAction::Char('>'));
+        state.action(PromptAction::Clear);
+        assert!(!state.can_redo(), "New changes clear redo");
+        state.action(PromptAction::Undo);
+        assert_eq!(state.value(), ">hello big world");
+        state.action(PromptAction::Undo);
+        assert_eq!(state.value(), "hello big world");
+
+        // Replacing the selection is a single step
+        state.select(0, 5);
+        state.action(PromptAction::Char('H'));
+        state.action(PromptAction::Char('i'));
+        assert_eq!(state.value(), "Hi big world");
+        state.action(PromptAction::Undo);
+        assert_eq!(state.value(), "hello big world");
+    }
+
+    #[test]
+    fn undo_depth() {
+        let mut state = PromptState::new("").undo_depth(2);
+
+        state.set_value("a".into());
+        state.set_value("b".into());
+        state.set_value("c".into());
+
+        assert!(state.undo());
+        assert!(state.undo());
+        assert!(!state.undo());
+        assert_eq!(state.value(), "a");
+    }
+
+    #[cfg(feature = "backend-event")]
+    #[test]
+    fn undo_keys() {
+        use crate::event::{KeyCode, KeyMod};
+
+        let mut state = PromptState::new("a");
+        state.handle_keys(Key(KeyMod::NONE, KeyCode::Char('b')));
+        state.handle_keys(Key(KeyMod::CTRL, KeyCode::Char('_')));
+        assert_eq!(state.value(), "a");
+        state.handle_keys(Key(KeyMod::CTRL | KeyMod::SHIFT, KeyCode::Char('z')));
+        assert_eq!(state.value(), "ab");
+    }
+
+    #[test]
+    fn kill_and_yank() {
+        let mut state = PromptState::new("one two three");
+
+        state.action(PromptAction::DeletePrevWord);
+        state.action(PromptAction::DeletePrevWord);
+        assert_eq!(state.value(), "one ");
+        assert_eq!(state.kill_ring.current(), Some("two three"), "Consecutive kills are joined");
+
+        state.action(PromptAction::MoveStart);
+        state.action(PromptAction::DeleteToEnd);
+        assert_eq!(state.value(), "");
+        assert_eq!(state.kill_ring.len(), 2);
+
+        state.action(PromptAction::Yank);
+        assert_eq!(state.value(), "one ");
+        assert!(state.action(PromptAction::YankPop));
+        assert_eq!(state.value(), "two three");
+        assert!(state.action(PromptAction::YankPop));
+        assert_eq!(state.value(), "one ", "Yank-pop wraps around");
+
+        state.action(PromptAction::MoveLeft(1));
+        assert!(!state.action(PromptAction::YankPop), "Only right after a yank");
+    }
+
+    #[test]
+    fn mark_and_select() {
+        let mut state = PromptState::new("hello world");
+
+        state.action(PromptAction::SelectPrevWord);
+        assert_eq!(state.selected_text(), Some("world"));
+        state.action(PromptAction::SelectLeft(1));
+        assert_eq!(state.selected_text(), Some(" world"));
+        state.action(PromptAction::MoveLeft(1));
+        assert_eq!(state.selection(), None, "Moving without the mark clears the selection");
+
+        state.action(PromptAction::MoveStart);
+        state.action(PromptAction::SetMark);
+        state.action(PromptAction::MoveNextWord);
+        assert_eq!(state.selected_text(), Some("hello"));
+
+        // Killing the selection
+        state.action(PromptAction::DeleteToEnd);
+        assert_eq!(state.value(), " world");
+        assert!(!state.is_mark_set());
+        state.action(PromptAction::MoveEnd);
+        state.action(PromptAction::Yank);
+        assert_eq!(state.value(), " worldhello");
+
+        state.action(PromptAction::SetMark);
+        state.action(PromptAction::MoveLeft(2));
+        state.action(PromptAction::ClearSelection);
+        assert_eq!(state.selection(), None);
+
+        // Selection is drawn
+        let mut buf = Buffer::empty(11, 1);
+        state.action(PromptAction::SelectLeft(2));
+        Prompt::new(&state).draw(&mut buf, Rect::new(0, 0, 11, 1));
+        assert_eq!(buf.get((8, 0)).map(|c| c.style), Some(Style::new(Color::Black, Color::Blue)));
+        assert_ne!(buf.get((9, 0)).map(|c| c.style), Some(Style::new(Color::Black, Color::Blue)));
+    }
+
+    #[test]
+    fn history() {
+        let mut state = PromptState::new("");
+        state.history = ["cargo build", "ls", "cargo test"].into_iter().collect();
+
+        state.action(PromptAction::String("car".into()));
+        state.action(PromptAction::HistoryPrefixPrev);
+        assert_eq!(state.value(), "cargo test");
+        state.action(PromptAction::HistoryPrefixPrev);
+        assert_eq!(state.value(), "cargo build");
+        assert!(!state.action(PromptAction::HistoryPrefixPrev), "No more entries");
+        state.action(PromptAction::HistoryPrefixNext);
+        state.action(PromptAction::HistoryPrefixNext);
+        assert_eq!(state.value(), "car", "The line being typed is restored");
+
+        state.action(PromptAction::HistoryPrev);
+        state.action(PromptAction::HistoryPrev);
+        assert_eq!(state.value(), "ls");
+
+        state.action(PromptAction::Char('!'));
+        assert_eq!(state.submit(), "ls!");
+        assert_eq!(state.value(), "");
+        assert_eq!(state.history.entries(), ["cargo build", "ls", "cargo test", "ls!"]);
+    }
+
+    #[test]
+    fn history_search() {
+        let mut state = PromptState::new("draft");
+        state.history = ["cargo build", "ls", "cargo test"].into_iter().collect();
+
+        state.action(PromptAction::SearchHistory);
+        assert_eq!(state.search_query(), Some(""));
+        state.search_push('c');
+        assert_eq!(state.value(), "cargo test");
+        state.search_push('a');
+        assert_eq!(state.value(), "cargo test", "The current match is kept");
+        state.search_history();
+        assert_eq!(state.value(), "cargo build");
+        assert_eq!(state.cursor(), 0);
+
+        state.search_push('x');
+        assert!(state.is_search_failed());
+        assert_eq!(state.value(), "cargo build");
+        state.search_pop();
+        assert!(!state.is_search_failed());
+
+        state.cancel_search();
+        assert_eq!((state.value().as_str(), state.cursor()), ("draft", 5));
+
+        state.search_history();
+        state.search_push('l');
+        state.action(PromptAction::MoveEnd);
+        assert_eq!(state.search_query(), None, "Other actions accept the match");
+        assert_eq!(state.value(), "ls");
+
+        let mut buf = Buffer::empty(32, 1);
+        state.search_history();
+        state.search_push('z');
+        Prompt::new(&state).draw(&mut buf, Rect::new(0, 0, 32, 1));
+        let line: String = buf.cells.iter().filter_map(|c| c.char.as_deref()).collect();
+        assert_eq!(line, "(failed reverse-i-search)`z': ls");
+    }
+
+    #[test]
+    fn completion() {
+        let completer = ListCompleter::new(["build", "bench", "test"]);
+        let mut state = PromptState::new("cargo b");
+
+        assert!(state.complete(&completer));
+        assert_eq!(state.suggestion(), Some("uild"));
+
+        state.action(PromptAction::CompleteNext);
+        assert_eq!(state.value(), "cargo build");
+        assert_eq!(state.suggestion(), None, "Hidden while cycling");
+        state.complete(&completer);
+        state.action(PromptAction::CompleteNext);
+        assert_eq!(state.value(), "cargo bench");
+        state.action(PromptAction::CompleteNext);
+        assert_eq!(state.value(), "cargo b", "Typed text after the last candidate");
+        state.action(PromptAction::CompletePrev);
+        assert_eq!((state.value().as_str(), state.selected_candidate()), ("cargo bench", Some(1)));
+
+        state.action(PromptAction::Char(' '));
+        assert!(state.candidates().is_empty());
+        state.action(PromptAction::Undo);
+        assert_eq!(state.value(), "cargo bench");
+        state.action(PromptAction::Undo);
+        assert_eq!(state.value(), "cargo b", "Each cycle is undone at once");
+
+        state.action(PromptAction::String("u".into()));
+        state.complete(&completer);
+        assert!(state.action(PromptAction::AcceptSuggestion));
+        assert_eq!(state.value(), "cargo build");
+
+        state.action(PromptAction::String(" t".into()));
+        state.complete(&completer);
+        state.action(PromptAction::CompleteNext);
+        assert_eq!(state.value(), "cargo build test");
+        assert!(state.candidates().is_empty(), "A single candidate is applied right away");
+    }
+
+    #[test]
+    fn draw_completion() {
+        let mut state = PromptState::new("b");
+        state.complete(&ListCompleter::new(["build", "bench"]));
+
+        let mut buf = Buffer::empty(10, 4);
+        Prompt::new(&state).dropdown(1).draw(&mut buf, Rect::new(0, 0, 10, 1));
+
+        let line = |y: u16| -> String {
+            (0..10).filter_map(|x| buf.get((x, y)).and_then(|c| c.char.clone())).collect()
+        };
+        assert_eq!(line(0), "build");
+        assert_eq!(buf.get((2, 0)).map(|c| c.style), Some(Color::LightBlack.into()));
+        assert_eq!(line(1), " build ");
+        assert_eq!(line(2), "");
+
+        // A dropdown without items is not drawn
+        state.action(PromptAction::CompleteNext);
+        let prompt = Prompt::new(&state).dropdown(0);
+        assert_eq!(prompt.dropdown_rect(Rect::new(0, 0, 10, 1)), None);
+        let mut buf = Buffer::empty(10, 4);
+        prompt.draw(&mut buf, Rect::new(0, 0, 10, 1));
+        assert_eq!(buf.get((1, 1)).and_then(|c| c.char.clone()), None);
+    }
+
+    #[test]
+    fn mask_and_placeholder() {
+        let line = |buf: &Buffer| -> String {
+            buf.cells.iter().filter_map(|c| c.char.as_deref()).collect()
+        };
+        let rect = Rect::new(0, 0, 4, 1);
+
+        let mut state = PromptState::new("").mask(PromptMask::Char('*'));
+        let mut buf = Buffer::empty(4, 1);
+        Prompt::new(&state).placeholder("Password").draw(&mut buf, rect);
+        assert_eq!(line(&buf), "Pass");
+
+        state.action(PromptAction::String("秘密abc".into()));
+        assert_eq!((state.cursor_pos(), state.width()), (5, 5), "Wide chars are masked with a single column");
+        assert_eq!(state.scroll(4), 2);
+        assert_eq!(state.index_at_col(1), 1);
+
+        let mut buf = Buffer::empty(4, 1);
+        Prompt::new(&state).placeholder("Password").draw(&mut buf, rect);
+        assert_eq!(line(&buf), "***");
+        assert_eq!(buf.get((3, 0)).map(|c| c.style), Some(Style::new(Color::Black, Color::LightGray)));
+
+        assert_eq!(state.submit(), "秘密abc");
+        assert!(state.history.is_empty(), "Secrets are not saved");
+
+        let mut state = PromptState::new("secret").mask(PromptMask::Hidden);
+        let mut buf = Buffer::empty(4, 1);
+        Prompt::new(&state).draw(&mut buf, rect);
+        assert_eq!(line(&buf), "");
+        assert_eq!(state.cursor_pos(), 0);
+        assert_eq!(buf.get((0, 0)).map(|c| c.style), Some(Style::new(Color::Black, Color::LightGray)));
+
+        state.action(PromptAction::DeleteLeft(1));
+        assert_eq!(state.value(), "secre");
+    }
+
+    #[test]
+    fn filters_and_limits() {
+        let mut state = PromptState::default().filter(CharFilter::Identifier).max_len(5);
+
+        assert!(!state.action(PromptAction::Char('-')), "Rejected");
+        state.action(PromptAction::String("my var_name".into()));
+        assert_eq!(state.value(), "myvar");
+        assert!(!state.action(PromptAction::Char('x')), "Too long");
+
+        state.select(0, 2);
+        state.action(PromptAction::Char('X'));
+        assert_eq!(state.value(), "Xvar", "Replaced text doesn't count");
+
+        let mut state = PromptState::default().max_width(5);
+        state.action(PromptAction::String("ab日本".into()));
+        assert_eq!(state.value(), "ab日");
+        state.set_value("programmatic".into());
+        assert_eq!(state.value(), "programmatic", "Only input is limited");
+    }
+
+    #[test]
+    fn validation() {
+        let mut state = PromptState::new("12").validator(|value: &str| {
+            match value.parse::<u8>() {
+                Ok(_) => Ok(()),
+                Err(_) => Err(format!("{value} is not a byte")),
+            }
+        });
+        assert!(state.is_valid());
+
+        state.action(PromptAction::Char('3'));
+        state.action(PromptAction::Char('4'));
+        assert_eq!(state.error(), Some("1234 is not a byte"));
+        state.action(PromptAction::Undo);
+        assert!(state.is_valid(), "Validated after undo");
+
+        state.action(PromptAction::Char('x'));
+        let mut buf = Buffer::empty(6, 2);
+        let drawn = Prompt::new(&state).focused(false).show_error(true).draw(&mut buf, Rect::new(0, 0, 6, 1));
+
+        assert_eq!(buf.get((0, 0)).map(|c| c.style), Some(Color::Red.into()));
+        let error: String = (0..6).filter_map(|x| buf.get((x, 1)).and_then(|c| c.char.clone())).collect();
+        assert_eq!(error, "12x is");
+        assert_eq!(drawn, Rect::new(0, 0, 6, 2), "Covers the error row");
+
+        let mut buf = Buffer::empty(6, 4);
+        let drawn = Prompt::new(&state).border_kind(BorderKind::Single).show_error(true).draw(&mut buf, Rect::new(0, 0, 6, 3));
+        assert_eq!(drawn, Rect::new(0, 0, 6, 4));
+        let drawn = Prompt::new(&state).draw(&mut buf, Rect::new(0, 0, 6, 3));
+        assert_eq!(drawn, Rect::new(0, 0, 6, 1), "Error is not shown");
+
+        // The error row is out of the buffer, but the position doesn't overflow
+        let mut buf = Buffer::empty(6, 1);
+        Prompt::new(&state).show_error(true).draw(&mut buf, Rect::new(0, u16::MAX, 6, 1));
+    }
+}
@@ -0,0 +1,772 @@
+use super::{PromptAction, PromptState};
+
+use crate::event::{Key, KeyCode as C, KeyMod};
+
+/// Prompt key bindings
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum PromptKeymap {
+    /// Emacs keymaps, like in most shells
+    #[default]
+    Emacs,
+    /// Vi keymaps with normal, insert and visual modes
+    /// The prompt starts in the insert mode
+    Vi,
+}
+
+/// Max count typed before a vi command, larger counts are clamped
+/// Keeps repeated commands (`p`, `r`, `u`, motions) from allocating or looping for too long
+const MAX_COUNT: usize = 10_000;
+
+/// Vi editing mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum ViMode {
+    /// Keys move the cursor and run commands
+    Normal,
+    /// Keys insert text, `Esc` switches to the normal mode
+    #[default]
+    Insert,
+    /// Keys move the cursor and extend the selection
+    Visual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+/// Search for a char in the value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Find {
+    char: char,
+    /// Whether to search before the cursor (`F` and `T`)
+    back: bool,
+    /// Whether to stop next to the char (`t` and `T`)
+    till: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    /// `w`
+    WordStart,
+    /// `e`
+    WordEnd,
+    /// `b`
+    WordBack,
+    /// `0`
+    LineStart,
+    /// `^`
+    FirstNonBlank,
+    /// `$`
+    LineEnd,
+    Find(Find),
+    /// `;` and `,`
+    RepeatFind { reverse: bool },
+    /// The whole value, for doubled operators like `dd`
+    Line,
+}
+
+/// Command waiting for a char
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    Find { back: bool, till: bool },
+    Replace,
+}
+
+/// Vi keymap state
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(super) struct Vi {
+    pub(super) mode: ViMode,
+    count: Option<usize>,
+    /// Operator and the count typed before it
+    operator: Option<(Operator, Option<usize>)>,
+    pending: Option<Pending>,
+    last_find: Option<Find>,
+    /// Keys of the command being typed, without counts
+    keys: Vec<Key>,
+    /// Count of the command being typed
+    change_count: Option<usize>,
+    /// Whether the text typed in the insert mode is a part of the change
+    recording: bool,
+    /// Count and keys of the last change, repeated by `.`
+    last_change: Option<(Option<usize>, Vec<Key>)>,
+}
+
+impl PromptState {
+    /// Set key bindings used by [PromptState::handle_keys]
+    pub fn keymap(mut self, keymap: PromptKeymap) -> Self {
+        self.keymap = keymap;
+        self.vi = Vi::default();
+        self
+    }
+
+    /// Current vi mode
+    /// Returns `None` if the keymap is not [PromptKeymap::Vi]
+    pub fn vi_mode(&self) -> Option<ViMode> {
+        (self.keymap == PromptKeymap::Vi).then_some(self.vi.mode)
+    }
+    /// Whether the vi visual mode is active
+    pub(super) fn is_vi_visual(&self) -> bool {
+        self.keymap == PromptKeymap::Vi && self.vi.mode == ViMode::Visual
+    }
+
+    /// Handle key events with the vi keymap
+    ///
+    /// # Notes
+    ///
+    /// - the insert mode uses the emacs keymap, except for `Esc`
+    /// - the normal mode supports motions (`h`, `l`, `w`, `b`, `e`, `0`, `^`, `$`, `f`, `t`, `F`, `T`, `;`, `,`),
+    ///   operators (`d`, `c`, `y`) with counts and `.` repeat
+    /// - counts are clamped to 10000
+    /// - `Enter` is not handled in any mode, so the prompt can be submitted
+    pub(super) fn handle_vi_keys(&mut self, key: Key) -> bool {
+        // The history search works the same in all modes
+        if self.search.is_some() {
+            return self.handle_emacs_keys(key);
+        }
+
+        match self.vi.mode {
+            ViMode::Insert => self.vi_insert_key(key),
+            ViMode::Normal | ViMode::Visual => self.vi_normal_key(key),
+        }
+    }
+    fn vi_insert_key(&mut self, key: Key) -> bool {
+        if self.vi.recording {
+            self.vi.keys.push(key);
+        }
+
+        match key {
+            Key(_, C::Esc) => {
+                if self.vi.recording {
+                    self.vi_done(true);
+                }
+                self.vi.mode = ViMode::Normal;
+                self.action(PromptAction::MoveLeft(1));
+                true
+            },
+            key => self.handle_emacs_keys(key),
+        }
+    }
+    fn vi_normal_key(&mut self, key: Key) -> bool {
+        use self::PromptAction as Action;
+
+        // The selection can be cleared by an edit
+        if self.vi.mode == ViMode::Visual && self.anchor.is_none() {
+            self.vi.mode = ViMode::Normal;
+        }
+
+        if let Some(pending) = self.vi.pending.take() {
+            let Key(KeyMod::NONE | KeyMod::SHIFT, C::Char(char)) = key else {
+                self.vi_done(false);
+                return false;
+            };
+
+            self.vi.keys.push(key);
+            return match pending {
+                Pending::Find { back, till } => self.vi_motion(Motion::Find(Find { char, back, till })),
+                Pending::Replace => self.vi_replace(char),
+            };
+        }
+
+        let char = match key {
+            Key(KeyMod::NONE | KeyMod::SHIFT, C::Char(char)) => char,
+            Key(KeyMod::NONE, C::Left | C::Backspace) => 'h',
+            Key(KeyMod::NONE, C::Right) => 'l',
+            Key(KeyMod::NONE, C::Home) => '0',
+            Key(KeyMod::NONE, C::End) => '$',
+            Key(KeyMod::NONE, C::Up) => 'k',
+            Key(KeyMod::NONE, C::Down) => 'j',
+            Key(KeyMod::CTRL, C::Char('r')) => {
+                let count = self.vi.count.unwrap_or(1);
+                let updated = (0..count).take_while(|_| self.redo()).count() > 0;
+                self.vi_done(false);
+                return updated;
+            },
+            Key(_, C::Esc) => {
+                let updated = self.vi.mode == ViMode::Visual || self.vi.operator.is_some() || self.vi.count.is_some();
+                self.vi_exit_visual();
+                self.vi_done(false);
+                return updated;
+            },
+            _ => return false,
+        };
+
+        // Counts before and after operators
+        if let Some(digit) = char.to_digit(10).filter(|d| *d > 0 || self.vi.count.is_some()) {
+            let count = self.vi.count.unwrap_or(0);
+            self.vi.count = Some(count.saturating_mul(10).saturating_add(digit as usize).min(MAX_COUNT));
+            return true;
+        }
+
+        if self.vi.keys.is_empty() {
+            self.vi.change_count = self.vi.count;
+        }
+        self.vi.keys.push(key);
+
+        let operator = match char {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        };
+        if let Some(operator) = operator {
+            return self.vi_operator(operator);
+        }
+
+        let motion = match char {
+            'h' => Some(Motion::Left),
+            'l' | ' ' => Some(Motion::Right),
+            'w' => Some(Motion::WordStart),
+            'e' => Some(Motion::WordEnd),
+            'b' => Some(Motion::WordBack),
+            '0' => Some(Motion::LineStart),
+            '^' => Some(Motion::FirstNonBlank),
+            '$' => Some(Motion::LineEnd),
+            ';' => Some(Motion::RepeatFind { reverse: false }),
+            ',' => Some(Motion::RepeatFind { reverse: true }),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            return self.vi_motion(motion);
+        }
+
+        let find = match char {
+            'f' => Some((false, false)),
+            't' => Some((false, true)),
+            'F' => Some((true, false)),
+            'T' => Some((true, true)),
+            _ => None,
+        };
+        if let Some((back, till)) = find {
+            self.vi.pending = Some(Pending::Find { back, till });
+            return true;
+        }
+
+        // Other commands can't follow operators
+        if self.vi.operator.is_some() {
+            self.vi_done(false);
+            return false;
+        }
+
+        if self.vi.mode == ViMode::Visual {
+            return self.vi_visual_command(char);
+        }
+
+        let count = self.vi.count.take();
+        let n = count.unwrap_or(1);
+        let cur = self.cursor;
+
+        match char {
+            'x' => self.vi_apply(Operator::Delete, cur..cur.saturating_add(n).min(self.len())),
+            'X' => self.vi_apply(Operator::Delete, cur.saturating_sub(n)..cur),
+            'D' => self.vi_apply(Operator::Delete, cur..self.len()),
+            'C' => self.vi_apply(Operator::Change, cur..self.len()),
+            's' => self.vi_apply(Operator::Change, cur..cur.saturating_add(n).min(self.len())),
+            'S' => self.vi_apply(Operator::Change, 0..self.len()),
+
+            'i' => self.vi_insert(cur),
+            'a' => self.vi_insert((cur + 1).min(self.len())),
+            'I' => self.vi_insert(self.first_non_blank()),
+            'A' => self.vi_insert(self.len()),
+
+            'p' => self.vi_paste(n, true),
+            'P' => self.vi_paste(n, false),
+            'r' => {
+                self.vi.count = count;
+                self.vi.pending = Some(Pending::Replace);
+                true
+            },
+            '~' => self.vi_toggle_case(n),
+
+            'u' => {
+                let updated = (0..n).take_while(|_| self.undo()).count() > 0;
+                self.vi_done(false);
+                updated
+            },
+            'v' => {
+                self.vi_done(false);
+                self.anchor = Some(self.cursor);
+                self.mark = true;
+                self.vi.mode = ViMode::Visual;
+                true
+            },
+            'k' => {
+                self.vi_done(false);
+                self.action(Action::HistoryPrev)
+            },
+            'j' => {
+                self.vi_done(false);
+                self.action(Action::HistoryNext)
+            },
+            '.' => self.vi_repeat(count),
+
+            _ => {
+                self.vi_done(false);
+                false
+            },
+        }
+    }
+    /// Command in the visual mode, applied to the selection
+    fn vi_visual_command(&mut self, char: char) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.vi_done(false);
+            return false;
+        };
+
+        let operator = match char {
+            'x' => Operator::Delete,
+            's' => Operator::Change,
+            'o' => {
+                // Move the cursor to the other end of the selection
+                if let Some(anchor) = self.anchor.replace(self.cursor) {
+                    self.cursor = anchor;
+                }
+                self.vi_done(false);
+                return true;
+            },
+            'v' => {
+                self.vi_exit_visual();
+                self.vi_done(false);
+                return true;
+            },
+            _ => {
+                self.vi_done(false);
+                return false;
+            },
+        };
+
+        self.vi_apply(operator, start..end)
+    }
+
+    /// Press an operator key
+    fn vi_operator(&mut self, operator: Operator) -> bool {
+        if self.vi.mode == ViMode::Visual {
+            let Some((start, end)) = self.selection() else {
+                self.vi_done(false);
+                return false;
+            };
+            return self.vi_apply(operator, start..end);
+        }
+
+        match self.vi.operator {
+            None => {
+                self.vi.operator = Some((operator, self.vi.count.take()));
+                true
+            },
+            // Doubled operators work on the whole value
+            Some((op, _)) if op == operator => self.vi_motion(Motion::Line),
+            Some(_) => {
+                self.vi_done(false);
+                false
+            },
+        }
+    }
+    /// Move the cursor, or apply the pending operator to the text it moves over
+    fn vi_motion(&mut self, motion: Motion) -> bool {
+        let (operator, op_count) = self.vi.operator.unwrap_or((Operator::Yank, None));
+        let is_operator = self.vi.operator.is_some();
+        let motion_count = self.vi.count.take();
+        let count = op_count.unwrap_or(1).saturating_mul(motion_count.unwrap_or(1)).min(MAX_COUNT);
+        let cur = self.cursor;
+
+        // `2d3w` is repeated as `d6w`
+        if op_count.is_some() || motion_count.is_some() {
+            self.vi.change_count = Some(count);
+        }
+
+        // `cw` changes to the end of the word, like `ce`
+        let motion = match motion {
+            Motion::WordStart if is_operator
+                && operator == Operator::Change
+                && self.word_ids().get(cur).is_some_and(Option::is_some) => Motion::WordEnd,
+            motion => motion,
+        };
+
+        let Some((target, inclusive)) = self.vi_target(motion, count) else {
+            self.vi_done(false);
+            return false;
+        };
+
+        if !is_operator {
+            let updated = self.action(PromptAction::MoveTo(target));
+            self.vi_done(false);
+            return updated;
+        }
+
+        let range = match motion {
+            Motion::Line => 0..self.len(),
+            _ => target.min(cur)..(target.max(cur) + inclusive as usize).min(self.len()),
+        };
+        self.vi_apply(operator, range)
+    }
+    /// Position the motion moves the cursor to and whether the grapheme at it is included
+    /// Returns `None` if the motion fails, for example if a char is not found
+    fn vi_target(&mut self, motion: Motion, count: usize) -> Option<(usize, bool)> {
+        let cur = self.cursor;
+        let len = self.len();
+
+        let target = match motion {
+            Motion::Left => (cur.saturating_sub(count), false),
+            Motion::Right => (cur.saturating_add(count).min(len), false),
+            Motion::WordStart => {
+                let ids = self.word_ids();
+                ((0..count).fold(cur, |pos, _| vi_word_start(&ids, pos)), false)
+            },
+            Motion::WordEnd => {
+                let ids = self.word_ids();
+                ((0..count).fold(cur, |pos, _| vi_word_end(&ids, pos)), true)
+            },
+            Motion::WordBack => ((0..count).fold(cur, |pos, _| pos - self.get_prev_word_width(pos)), false),
+            Motion::LineStart => (0, false),
+            Motion::FirstNonBlank => (self.first_non_blank(), false),
+            Motion::LineEnd => (len, false),
+            Motion::Find(find) => {
+                self.vi.last_find = Some(find);
+                self.vi_find(find, count)?
+            },
+            Motion::RepeatFind { reverse } => {
+                let find = self.vi.last_find?;
+                self.vi_find(Find { back: find.back != reverse, ..find }, count)?
+            },
+            Motion::Line => (0, false),
+        };
+
+        Some(target)
+    }
+    /// Position of the `count`-th char found by `find`
+    fn vi_find(&self, find: Find, count: usize) -> Option<(usize, bool)> {
+        let mut buf = [0; 4];
+        let char = &*find.char.encode_utf8(&mut buf);
+        let cur = self.cursor;
+
+        if find.back {
+            let pos = (0..cur).rev().filter(|i| self.char_at(*i) == Some(char)).nth(count - 1)?;
+            Some((if find.till { pos + 1 } else { pos }, false))
+        } else {
+            let pos = (cur + 1..self.len()).filter(|i| self.char_at(*i) == Some(char)).nth(count - 1)?;
+            // `t` doesn't move if the char is next to the cursor
+            Some(if find.till { (pos - 1, pos - 1 > cur) } else { (pos, true) })
+        }
+    }
+    /// Position of the first grapheme that is not whitespace
+    fn first_non_blank(&self) -> usize {
+        (0..self.len())
+            .find(|i| !self.char_at(*i).is_some_and(|g| g.chars().all(char::is_whitespace)))
+            .unwrap_or(self.len())
+    }
+
+    /// Apply an operator to graphemes in `range`
+    /// Deleted and yanked text is put to the kill ring
+    /// Changes made in the visual mode can't be repeated
+    fn vi_apply(&mut self, operator: Operator, range: std::ops::Range<usize>) -> bool {
+        let visual = self.vi.mode == ViMode::Visual;
+        self.vi_exit_visual();
+
+        match operator {
+            Operator::Delete => {
+                let updated = !range.is_empty() && self.kill(range, false, false);
+                self.vi_done(updated && !visual);
+                updated
+            },
+            Operator::Change => {
+                if !range.is_empty() {
+                    self.kill(range, false, false);
+                }
+                self.vi_insert(self.cursor);
+                if visual {
+                    self.vi.keys.clear();
+                    self.vi.recording = false;
+                }
+                true
+            },
+            Operator::Yank => {
+                let text = &self.value[self.byte_at(range.start)..self.byte_at(range.end)];
+                if !text.is_empty() {
+                    self.kill_ring.push(text);
+                }
+                self.move_to(range.start);
+                self.vi_done(false);
+                true
+            },
+        }
+    }
+    /// Switch to the insert mode at `pos`, the typed text is a part of the change
+    fn vi_insert(&mut self, pos: usize) -> bool {
+        self.move_to(pos);
+        self.vi.mode = ViMode::Insert;
+        self.vi.recording = true;
+        self.vi.operator = None;
+        self.vi.count = None;
+        true
+    }
+    /// Insert the kill ring entry `count` times after or before the cursor
+    fn vi_paste(&mut self, count: usize, after: bool) -> bool {
+        let Some(text) = self.kill_ring.current().map(|t| t.repeat(count.min(MAX_COUNT))) else {
+            self.vi_done(false);
+            return false;
+        };
+
+        let pos = if after { (self.cursor + 1).min(self.len()) } else { self.cursor };
+        let len = self.len();
+        let updated = self.push_string(pos, text);
+        if updated {
+            // The cursor stays on the last pasted grapheme
+            self.move_to(pos + self.len() - len - 1);
+        }
+        self.vi_done(updated);
+        updated
+    }
+    /// Replace `count` graphemes with the char
+    fn vi_replace(&mut self, char: char) -> bool {
+        let count = self.vi.count.take().unwrap_or(1);
+        let range = self.cursor..self.cursor.saturating_add(count);
+        if range.end > self.len() {
+            self.vi_done(false);
+            return false;
+        }
+
+        let text = char.to_string().repeat(count);
+        if self.filter_text(range.clone(), &text) != text {
+            self.vi_done(false);
+            return false;
+        }
+
+        self.edit(range.clone(), &text);
+        self.move_to(range.end - 1);
+        self.vi_done(true);
+        true
+    }
+    /// Toggle case of `count` graphemes and move the cursor after them
+    fn vi_toggle_case(&mut self, count: usize) -> bool {
+        let range = self.cursor..self.cursor.saturating_add(count).min(self.len());
+        let text: String = self.value[self.byte_at(range.start)..self.byte_at(range.end)]
+            .chars()
+            .map(|c| if c.is_lowercase() { c.to_uppercase().to_string() } else { c.to_lowercase().to_string() })
+            .collect();
+
+        if range.is_empty() {
+            self.vi_done(false);
+            return false;
+        }
+
+        self.edit(range.clone(), &text);
+        self.move_to(range.end);
+        self.vi_done(true);
+        true
+    }
+    /// Repeat the last change, with a new count if it's given
+    fn vi_repeat(&mut self, count: Option<usize>) -> bool {
+        self.vi.keys.clear();
+        let Some((last_count, keys)) = self.vi.last_change.clone() else {
+            return false;
+        };
+
+        self.vi.count = count.or(last_count);
+        let mut updated = false;
+        for key in keys {
+            updated |= self.handle_vi_keys(key);
+        }
+        updated
+    }
+
+    fn vi_exit_visual(&mut self) {
+        if self.vi.mode == ViMode::Visual {
+            self.clear_selection();
+            self.vi.mode = ViMode::Normal;
+        }
+    }
+    /// Finish the command
+    /// When `change` is `true`, the command can be repeated with `.`
+    fn vi_done(&mut self, change: bool) {
+        let keys = std::mem::take(&mut self.vi.keys);
+        if change {
+            self.vi.last_change = Some((self.vi.change_count, keys));
+        }
+
+        self.vi.count = None;
+        self.vi.operator = None;
+        self.vi.pending = None;
+        self.vi.recording = false;
+
+        // The cursor is on a grapheme in the normal mode
+        if self.vi.mode != ViMode::Insert && self.cursor >= self.len() {
+            self.cursor = self.len().saturating_sub(1);
+        }
+    }
+}
+
+/// Start of the next word, `ids` are word indices of graphemes
+fn vi_word_start(ids: &[Option<usize>], mut pos: usize) -> usize {
+    let word = ids.get(pos).copied().flatten();
+    while word.is_some() && pos < ids.len() && ids[pos] == word {
+        pos += 1;
+    }
+    while pos < ids.len() && ids[pos].is_none() {
+        pos += 1;
+    }
+    pos
+}
+/// Last grapheme of the word, or of the next word if `pos` is at the end of the word
+fn vi_word_end(ids: &[Option<usize>], mut pos: usize) -> usize {
+    pos += 1;
+    while pos < ids.len() && ids[pos].is_none() {
+        pos += 1;
+    }
+    while pos + 1 < ids.len() && ids[pos + 1].is_some() && ids[pos + 1] == ids[pos] {
+        pos += 1;
+    }
+    pos.min(ids.len().saturating_sub(1))
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Press keys, `\x1b` is `Esc`
+    fn press(state: &mut PromptState, keys: &str) {
+        for char in keys.chars() {
+            let key = match char {
+                '\x1b' => Key(KeyMod::NONE, C::Esc),
+                char => Key(KeyMod::NONE, C::Char(char)),
+            };
+            state.handle_keys(key);
+        }
+    }
+    fn vi(value: &str) -> PromptState {
+        let mut state = PromptState::new(value).keymap(PromptKeymap::Vi);
+        press(&mut state, "\x1b0");
+        state
+    }
+
+    #[test]
+    fn modes() {
+        let mut state = PromptState::new("ab").keymap(PromptKeymap::Vi);
+        assert_eq!(state.vi_mode(), Some(ViMode::Insert));
+        assert_eq!(PromptState::new("ab").vi_mode(), None);
+
+        press(&mut state, "c\x1b");
+        assert_eq!(state.value(), "abc");
+        assert_eq!(state.vi_mode(), Some(ViMode::Normal));
+        assert_eq!(state.cursor(), 2, "Esc moves the cursor left");
+
+        press(&mut state, "$l");
+        assert_eq!(state.cursor(), 2, "The cursor stays on the last grapheme");
+
+        press(&mut state, "0vl");
+        assert_eq!(state.vi_mode(), Some(ViMode::Visual));
+        assert_eq!(state.selection(), Some((0, 2)));
+        press(&mut state, "d");
+        assert_eq!(state.value(), "c");
+        assert_eq!(state.vi_mode(), Some(ViMode::Normal));
+
+        press(&mut state, "A!\x1b");
+        assert_eq!(state.value(), "c!");
+        state.submit();
+        assert_eq!(state.vi_mode(), Some(ViMode::Insert));
+    }
+
+    #[test]
+    fn motions() {
+        let mut state = vi("one two-three  four");
+
+        let mut cursor = |keys: &str| {
+            press(&mut state, keys);
+            state.cursor()
+        };
+        assert_eq!(cursor("w"), 4);
+        assert_eq!(cursor("e"), 6, "`-` separates words");
+        assert_eq!(cursor("e"), 12);
+        assert_eq!(cursor("3b"), 0);
+        assert_eq!(cursor("$"), 18);
+        assert_eq!(cursor("0f-"), 7);
+        assert_eq!(cursor("Fo"), 6);
+        assert_eq!(cursor("0tw"), 4);
+        assert_eq!(cursor(";"), 4, "`t` doesn't move next to the char");
+        assert_eq!(cursor("2fe"), 12);
+        assert_eq!(cursor(","), 11);
+    }
+
+    #[test]
+    fn operators() {
+        let mut state = vi("a b c d e f g h");
+        press(&mut state, "d2w");
+        assert_eq!(state.value(), "c d e f g h");
+        press(&mut state, ".");
+        assert_eq!(state.value(), "e f g h");
+        press(&mut state, "3.");
+        assert_eq!(state.value(), "h");
+        press(&mut state, "u");
+        assert_eq!(state.value(), "e f g h");
+
+        let mut state = vi("one two");
+        press(&mut state, "cwONE\x1b");
+        assert_eq!(state.value(), "ONE two");
+        assert_eq!(state.vi_mode(), Some(ViMode::Normal));
+        press(&mut state, "w.");
+        assert_eq!(state.value(), "ONE ONE");
+
+        let mut state = vi("a-b-c");
+        press(&mut state, "ldt-");
+        assert_eq!(state.value(), "a-c");
+        press(&mut state, "dd");
+        assert_eq!(state.value(), "");
+        assert_eq!(state.kill_ring.current(), Some("a-c"));
+    }
+
+    #[test]
+    fn commands() {
+        let mut state = vi("abcdef");
+        press(&mut state, "3x");
+        assert_eq!(state.value(), "def");
+        press(&mut state, "p");
+        assert_eq!(state.value(), "dabcef");
+        assert_eq!(state.cursor(), 3);
+
+        press(&mut state, "0y2lP");
+        assert_eq!(state.value(), "dadabcef");
+
+        press(&mut state, "$2r!");
+        assert_eq!(state.value(), "dadabcef", "Not enough chars to replace");
+        press(&mut state, "0r!l2~");
+        assert_eq!(state.value(), "!ADabcef");
+
+        press(&mut state, "0vlly");
+        assert_eq!(state.kill_ring.current(), Some("!AD"));
+        assert_eq!(state.vi_mode(), Some(ViMode::Normal));
+
+        press(&mut state, "lsX\x1b");
+        assert_eq!(state.value(), "!XDabcef");
+        press(&mut state, "$.");
+        assert_eq!(state.value(), "!XDabceX");
+    }
+
+    #[test]
+    fn huge_counts() {
+        let huge = "99999999999999999999";
+
+        let mut state = vi("ab cd ef");
+        press(&mut state, &format!("{huge}l"));
+        assert_eq!(state.cursor(), 7);
+        press(&mut state, &format!("0{huge}x"));
+        assert_eq!(state.value(), "");
+
+        let mut state = vi("ab cd ef");
+        press(&mut state, &format!("{huge}d3w"));
+        assert_eq!(state.value(), "");
+        press(&mut state, &format!("{huge}p"));
+        assert_eq!(state.value().len(), "ab cd ef".len() * MAX_COUNT, "Count is clamped");
+        press(&mut state, &format!("{huge}u"));
+        assert_eq!(state.value(), "ab cd ef", "Undo stops when nothing is left");
+
+        let mut state = vi("ab");
+        press(&mut state, &format!("{huge}r!{huge}s"));
+        assert_eq!(state.value(), "");
+    }
+}
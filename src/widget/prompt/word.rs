@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How to split the prompt value into words
+/// Word motions and deletions (like [PromptAction::MoveNextWord](super::PromptAction::MoveNextWord)
+/// and [PromptAction::DeletePrevWord](super::PromptAction::DeletePrevWord)) skip separators and then a word
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::widget::prompt::WordMode;
+/// let value = "cd ~/src/my-app";
+/// let words = |mode: WordMode| -> Vec<&str> {
+///     mode.words(value).into_iter().map(|r| &value[r]).collect()
+/// };
+///
+/// assert_eq!(words(WordMode::Unicode), ["cd", "src", "my", "app"]);
+/// assert_eq!(words(WordMode::Whitespace), ["cd", "~/src/my-app"]);
+/// assert_eq!(words(WordMode::Path), ["cd", "~", "src", "my-app"]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all="snake_case"))]
+pub enum WordMode {
+    /// Unicode word boundaries, words are separated by whitespace and punctuation
+    /// CJK ideographs are words of their own
+    #[default]
+    Unicode,
+    /// Words are separated by whitespace only, like shell arguments
+    Whitespace,
+    /// Words are separated by whitespace and `/`, like file paths
+    Path,
+    /// Words are separated by whitespace and the chars of the string
+    Separators(String),
+}
+impl WordMode {
+    /// Byte ranges of words in `s`
+    pub fn words(&self, s: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Unicode => s
+                .split_word_bound_indices()
+                .filter(|(_, word)| word.chars().any(char::is_alphanumeric))
+                .map(|(i, word)| i..i + word.len())
+                .collect(),
+            Self::Whitespace => split(s, char::is_whitespace),
+            Self::Path => split(s, |c| c.is_whitespace() || c == '/'),
+            Self::Separators(seps) => split(s, |c| c.is_whitespace() || seps.contains(c)),
+        }
+    }
+}
+
+/// Byte ranges of runs of chars that are not separators
+fn split<F: Fn(char) -> bool>(s: &str, is_sep: F) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = None;
+
+    for (i, char) in s.char_indices() {
+        match (is_sep(char), start) {
+            (true, Some(word_start)) => {
+                words.push(word_start..i);
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+
+    if let Some(word_start) = start {
+        words.push(word_start..s.len());
+    }
+
+    words
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words<'a>(mode: &WordMode, s: &'a str) -> Vec<&'a str> {
+        mode.words(s).into_iter().map(|r| &s[r]).collect()
+    }
+
+    #[test]
+    fn unicode() {
+        let mode = WordMode::Unicode;
+
+        assert_eq!(words(&mode, "don't stop_me now!"), ["don't", "stop_me", "now"]);
+        assert_eq!(words(&mode, "日本語 text"), ["日", "本", "語", "text"]);
+        assert_eq!(words(&mode, "é́ (x)"), ["é́", "x"]);
+    }
+
+    #[test]
+    fn separators() {
+        let mode = WordMode::Separators(",=".into());
+
+        assert_eq!(words(&mode, "a=1,b=2 c"), ["a", "1", "b", "2", "c"]);
+        assert_eq!(words(&WordMode::Path, "/usr//lib/ "), ["usr", "lib"]);
+    }
+}
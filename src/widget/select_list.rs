@@ -0,0 +1,697 @@
+use crate::{buffer::Buffer, layout::Rect, style::Style};
+
+use super::Draw;
+
+use std::collections::BTreeSet;
+
+use unicode_width::UnicodeWidthStr;
+
+#[cfg(feature = "backend-event")]
+use crate::event::{Event, Key, Mouse, MouseBtn};
+
+/// Select list message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListAction {
+    /// Select the item `n` items below, or the first one if nothing is selected
+    Next(usize),
+    /// Select the item `n` items above, or the first one if nothing is selected
+    Prev(usize),
+    /// Select the first item
+    First,
+    /// Select the last item
+    Last,
+    /// Select the item a page above
+    PageUp,
+    /// Select the item a page below
+    PageDown,
+    /// Select the item at the index
+    Select(usize),
+    /// Clear the selection
+    Unselect,
+
+    /// Mark or unmark the selected item, does nothing if multi-selection is disabled
+    ToggleMark,
+    /// Mark all items, does nothing if multi-selection is disabled
+    MarkAll,
+    /// Unmark all items
+    ClearMarks,
+
+    /// Scroll the view up, the selection follows when it leaves the view
+    ScrollUp(usize),
+    /// Scroll the view down, the selection follows when it leaves the view
+    ScrollDown(usize),
+}
+impl ListAction {
+    fn is_scroll(&self) -> bool {
+        matches!(self, Self::ScrollUp(_) | Self::ScrollDown(_))
+    }
+}
+
+/// Select list state
+/// Holds the selected item, the scroll offset and the marked items of a [SelectList]
+///
+/// # Notes
+///
+/// - the state only knows the number of items, update it with [ListState::set_len] when the items change
+/// - the view is scrolled to keep the selected item visible, it depends on the viewport size,
+///   it's updated by [ListState::handle_event] or [ListState::set_viewport]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListState {
+    /// Whether items can be marked, see [ListAction::ToggleMark]
+    pub multi_select: bool,
+    /// Number of items kept visible above and below the selected one
+    pub scroll_padding: usize,
+    /// Number of rows of each item
+    pub item_height: u16,
+
+    /// Number of items
+    len: usize,
+    selected: Option<usize>,
+    /// First visible item
+    offset: usize,
+    marked: BTreeSet<usize>,
+    /// Number of visible items
+    page: usize,
+}
+impl ListState {
+    /// Creates a [ListState] with `len` items, the first one is selected
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            selected: (len > 0).then_some(0),
+            ..Default::default()
+        }
+    }
+
+    //
+
+    /// Set whether items can be marked
+    pub fn multi_select(mut self, value: bool) -> Self {
+        self.multi_select = value;
+        self
+    }
+    /// Set number of items kept visible above and below the selected one
+    pub fn scroll_padding(mut self, value: usize) -> Self {
+        self.scroll_padding = value;
+        self
+    }
+    /// Set number of rows of each item
+    pub fn item_height(mut self, value: u16) -> Self {
+        self.item_height = value.max(1);
+        self
+    }
+    /// Set height of the area the list is drawn in
+    pub fn viewport(mut self, height: u16) -> Self {
+        self.set_viewport(height);
+        self
+    }
+
+    /// Handle events
+    /// Handles key presses (see [ListState::handle_keys]) and mouse (see [ListState::handle_mouse])
+    /// `area` is the rect the list is drawn in
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_event(&mut self, event: &Event, area: Rect) -> bool {
+        self.set_viewport(area.height);
+
+        match event {
+            Event::Key(key, _, info) if info.is_press() => self.handle_keys(*key),
+            Event::Mouse(mouse, _, _) => self.handle_mouse(*mouse, area),
+            _ => false
+        }
+    }
+    /// Handle mouse events
+    /// Left click selects an item, the wheel scrolls the view
+    /// `area` is the rect the list is drawn in
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_mouse(&mut self, mouse: Mouse, area: Rect) -> bool {
+        match mouse {
+            Mouse::Down(_, MouseBtn::Left, x, y) if area.contains((x, y)) => {
+                let index = self.offset + ((y - area.y) / self.item_height.max(1)) as usize;
+                index < self.len && self.action(ListAction::Select(index))
+            },
+            Mouse::ScrollUp(_, x, y) if area.contains((x, y)) => self.action(ListAction::ScrollUp(1)),
+            Mouse::ScrollDown(_, x, y) if area.contains((x, y)) => self.action(ListAction::ScrollDown(1)),
+            _ => false
+        }
+    }
+    /// Handle key events
+    /// `j`/`k` and arrows move the selection, `Home`/`End` (`g`/`G`) jump to the first/last item,
+    /// `PageUp`/`PageDown` move by a page and `Space` marks the selected item if multi-selection is enabled
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_keys(&mut self, key: Key) -> bool {
+        use self::ListAction as Action;
+        use crate::event::{KeyCode as C, KeyMod};
+
+        match key {
+            Key(KeyMod::NONE, C::Down | C::Char('j')) => self.action(Action::Next(1)),
+            Key(KeyMod::NONE, C::Up | C::Char('k')) => self.action(Action::Prev(1)),
+            Key(KeyMod::NONE, C::Home | C::Char('g')) => self.action(Action::First),
+            Key(KeyMod::NONE, C::End) |
+            Key(KeyMod::NONE | KeyMod::SHIFT, C::Char('G')) => self.action(Action::Last),
+            Key(_, C::PageUp) => self.action(Action::PageUp),
+            Key(_, C::PageDown) => self.action(Action::PageDown),
+
+            Key(KeyMod::NONE, C::Char(' ')) if self.multi_select => self.action(Action::ToggleMark),
+
+            _ => false
+        }
+    }
+
+    /// Apply an action to the state
+    /// Returns whether the state has been updated or not
+    pub fn action(&mut self, msg: ListAction) -> bool {
+        use self::ListAction as Msg;
+
+        let scroll = msg.is_scroll();
+        let page = self.page.max(1);
+        let last = self.len.saturating_sub(1);
+
+        let updated = match msg {
+            Msg::Next(n) => self.select(self.selected.map_or(0, |i| i.saturating_add(n))),
+            Msg::Prev(n) => self.select(self.selected.map_or(0, |i| i.saturating_sub(n))),
+            Msg::First => self.select(0),
+            Msg::Last => self.select(last),
+            Msg::PageUp => self.select(self.selected.map_or(0, |i| i.saturating_sub(page))),
+            Msg::PageDown => self.select(self.selected.map_or(0, |i| i.saturating_add(page))),
+            Msg::Select(index) => self.select(index),
+            Msg::Unselect => self.unselect(),
+
+            Msg::ToggleMark => match self.selected {
+                Some(index) => self.toggle_mark(index),
+                None => false
+            },
+            Msg::MarkAll => self.mark_all(),
+            Msg::ClearMarks => self.clear_marks(),
+
+            Msg::ScrollUp(n) => self.scroll_up(n),
+            Msg::ScrollDown(n) => self.scroll_down(n),
+        };
+
+        if !scroll {
+            self.scroll_to_selected();
+        }
+
+        updated
+    }
+
+    // Selection
+
+    /// Select the item at the index, clamped to the last item
+    /// Does nothing if the list is empty
+    pub fn select(&mut self, index: usize) -> bool {
+        if self.len == 0 {
+            return false;
+        }
+
+        let index = Some(index.min(self.len - 1));
+        let updated = index != self.selected;
+        self.selected = index;
+        updated
+    }
+    /// Clear the selection
+    pub fn unselect(&mut self) -> bool {
+        self.selected.take().is_some()
+    }
+
+    /// Mark or unmark the item at the index
+    /// Does nothing if multi-selection is disabled
+    pub fn toggle_mark(&mut self, index: usize) -> bool {
+        if !self.multi_select || index >= self.len {
+            return false;
+        }
+
+        if !self.marked.remove(&index) {
+            self.marked.insert(index);
+        }
+        true
+    }
+    /// Mark all items
+    /// Does nothing if multi-selection is disabled
+    pub fn mark_all(&mut self) -> bool {
+        if !self.multi_select || self.marked.len() == self.len {
+            return false;
+        }
+
+        self.marked.extend(0..self.len);
+        true
+    }
+    /// Unmark all items
+    pub fn clear_marks(&mut self) -> bool {
+        let updated = !self.marked.is_empty();
+        self.marked.clear();
+        updated
+    }
+
+    // Scrolling
+
+    /// Set height of the area the list is drawn in
+    pub fn set_viewport(&mut self, height: u16) {
+        self.page = (height / self.item_height.max(1)) as usize;
+        self.scroll_to_selected();
+    }
+    /// Scroll the view up by `amount` items
+    /// The selection moves up when it would leave the view
+    pub fn scroll_up(&mut self, amount: usize) -> bool {
+        let offset = self.offset.saturating_sub(amount);
+        let updated = offset != self.offset;
+        self.offset = offset;
+
+        // Keep the selection in the view
+        if let Some(index) = self.selected.filter(|_| self.page > 0) {
+            let padding = self.padding(self.page);
+            let max = (self.offset + self.page - 1).saturating_sub(padding);
+            self.selected = Some(index.min(max.max(self.offset)));
+        }
+
+        updated
+    }
+    /// Scroll the view down by `amount` items, the last page stays full
+    /// The selection moves down when it would leave the view
+    pub fn scroll_down(&mut self, amount: usize) -> bool {
+        let offset = self.offset
+            .saturating_add(amount)
+            .min(self.len.saturating_sub(self.page.max(1)));
+        let updated = offset != self.offset;
+        self.offset = offset;
+
+        // Keep the selection in the view
+        if let Some(index) = self.selected.filter(|_| self.page > 0) {
+            let min = (self.offset + self.padding(self.page)).min(self.len - 1);
+            self.selected = Some(index.max(min));
+        }
+
+        updated
+    }
+    /// Scroll the view, so the selected item is visible
+    /// Does nothing if the viewport size is unknown
+    pub fn scroll_to_selected(&mut self) {
+        if self.page > 0 {
+            self.offset = self.offset_in(self.page);
+        }
+    }
+
+    /// Scroll padding, limited to fit into the page
+    fn padding(&self, page: usize) -> usize {
+        self.scroll_padding.min(page.saturating_sub(1) / 2)
+    }
+    /// Returns the first visible item when `page` items are visible,
+    /// starting from the current offset and keeping the selected item visible
+    fn offset_in(&self, page: usize) -> usize {
+        let max = self.len.saturating_sub(page);
+
+        match self.selected {
+            Some(index) => {
+                let padding = self.padding(page);
+                let min_offset = (index + padding + 1).saturating_sub(page);
+                let max_offset = index.saturating_sub(padding).max(min_offset);
+
+                self.offset
+                    .clamp(min_offset, max_offset)
+                    .min(max)
+            },
+            None => self.offset.min(max)
+        }
+    }
+
+    // Getters
+
+    /// Get number of items
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Set number of items
+    /// The selection is clamped to the last item and marks of removed items are cleared
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.selected = self.selected
+            .filter(|_| len > 0)
+            .map(|index| index.min(len - 1));
+        self.marked.retain(|index| *index < len);
+        self.scroll_to_selected();
+    }
+
+    /// Get the selected item index
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+    /// Get the first visible item index
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Returns `true` if the item at the index is marked
+    pub fn is_marked(&self, index: usize) -> bool {
+        self.marked.contains(&index)
+    }
+    /// Get marked item indexes in ascending order
+    pub fn marked(&self) -> impl Iterator<Item = usize> + '_ {
+        self.marked.iter().copied()
+    }
+}
+impl Default for ListState {
+    fn default() -> Self {
+        Self {
+            multi_select: false,
+            scroll_padding: 0,
+            item_height: 1,
+            len: 0,
+            selected: None,
+            offset: 0,
+            marked: BTreeSet::new(),
+            page: 0,
+        }
+    }
+}
+
+/// Select list
+/// Draws the visible items of a list with the selected item highlighted
+/// Each item is drawn in a rect of [ListState::item_height] rows, the selection and scroll offset are taken from [ListState]
+///
+/// # Examples
+///
+/// ```
+/// use tuich::{buffer::Buffer, layout::Rect, style::Color, text::Text, widget::{select_list::ListState, Draw, SelectList}};
+///
+/// let items = ["one", "two", "three"];
+/// let state = ListState::new(items.len());
+///
+/// let mut buf = Buffer::empty(10, 2);
+/// SelectList::new(&state, items.map(Text::from))
+///     .highlight_style(Color::Green)
+///     .highlight_symbol("> ")
+///     .draw(&mut buf, Rect::sized(10, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectList<'a, W> {
+    pub state: &'a ListState,
+    pub items: Vec<W>,
+    pub style: Style,
+    pub highlight_style: Style,
+    /// Symbol drawn before the selected item
+    pub highlight_symbol: Option<String>,
+    pub marked_style: Style,
+    /// Symbol drawn before marked items
+    pub marked_symbol: Option<String>,
+}
+impl<'a, W: Draw> SelectList<'a, W> {
+    pub fn new<I: Into<Vec<W>>>(state: &'a ListState, items: I) -> Self {
+        Self {
+            state,
+            items: items.into(),
+            style: Style::default(),
+            highlight_style: Style::default().reverse(true),
+            highlight_symbol: None,
+            marked_style: Style::default().bold(true),
+            marked_symbol: None,
+        }
+    }
+
+    //
+
+    /// Set list style
+    pub fn style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.style = style.into();
+        self
+    }
+    /// Set selected item style
+    pub fn highlight_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.highlight_style = style.into();
+        self
+    }
+    /// Set symbol drawn before the selected item
+    pub fn highlight_symbol<S: ToString>(mut self, symbol: S) -> Self {
+        self.highlight_symbol = Some(symbol.to_string());
+        self
+    }
+    /// Set marked items style
+    pub fn marked_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.marked_style = style.into();
+        self
+    }
+    /// Set symbol drawn before marked items
+    pub fn marked_symbol<S: ToString>(mut self, symbol: S) -> Self {
+        self.marked_symbol = Some(symbol.to_string());
+        self
+    }
+}
+
+impl<'a, W: Draw> Draw for SelectList<'a, W> {
+    fn draw(self, buf: &mut Buffer, rect: Rect) -> Rect {
+        let state = self.state;
+        let item_height = state.item_height.max(1);
+        let page = (rect.height / item_height) as usize;
+        let offset = state.offset_in(page);
+
+        // Items are shifted by the widest symbol, so they don't move when selected
+        let symbol_width = [&self.highlight_symbol, &self.marked_symbol]
+            .iter()
+            .filter_map(|s| s.as_ref().map(|s| s.width()))
+            .max()
+            .unwrap_or(0) as u16;
+
+        let mut height = 0u16;
+
+        for (index, item) in self.items.into_iter().enumerate().skip(offset).take(page) {
+            let item_rect = rect
+                .add_y(height)
+                .with_height(item_height);
+            height += item_height;
+
+            set_rect_style(buf, item_rect, self.style);
+
+            item.draw(buf, item_rect.margin_left(symbol_width));
+
+            let selected = state.selected == Some(index);
+            let marked = state.is_marked(index);
+
+            let symbol =
+                if selected && self.highlight_symbol.is_some() { self.highlight_symbol.as_ref() }
+                else if marked { self.marked_symbol.as_ref() }
+                else { None };
+            if let Some(symbol) = symbol {
+                buf.set_clamped_string(item_rect.pos(), 0, symbol, (), symbol_width);
+            }
+
+            if marked {
+                set_rect_style(buf, item_rect, self.marked_style);
+            }
+            if selected {
+                set_rect_style(buf, item_rect, self.highlight_style);
+            }
+        }
+
+        rect.with_height(height)
+    }
+}
+
+/// Apply a style to all cells in the rect
+fn set_rect_style(buf: &mut Buffer, rect: Rect, style: Style) {
+    for y in rect.top()..rect.bottom() {
+        for x in rect.left()..rect.right() {
+            buf.set_style((x, y), style);
+        }
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection() {
+        let mut state = ListState::new(5).viewport(3);
+
+        state.action(ListAction::Next(1));
+        assert_eq!(state.selected(), Some(1));
+        state.action(ListAction::Next(10));
+        assert_eq!(state.selected(), Some(4));
+        assert!(!state.action(ListAction::Last));
+        state.action(ListAction::PageUp);
+        assert_eq!(state.selected(), Some(1));
+        state.action(ListAction::First);
+        assert_eq!(state.selected(), Some(0));
+
+        state.action(ListAction::Unselect);
+        assert_eq!(state.selected(), None);
+        state.action(ListAction::Prev(1));
+        assert_eq!(state.selected(), Some(0));
+
+        state.action(ListAction::Select(3));
+        state.set_len(2);
+        assert_eq!(state.selected(), Some(1));
+        state.set_len(0);
+        assert_eq!(state.selected(), None);
+        assert!(!state.action(ListAction::Next(1)));
+    }
+
+    #[test]
+    fn marks() {
+        let mut state = ListState::new(4);
+
+        // Multi-selection is disabled
+        assert!(!state.action(ListAction::ToggleMark));
+
+        state.multi_select = true;
+        state.action(ListAction::ToggleMark);
+        state.action(ListAction::Select(2));
+        state.action(ListAction::ToggleMark);
+        assert_eq!(state.marked().collect::<Vec<_>>(), [0, 2]);
+
+        state.action(ListAction::ToggleMark);
+        assert_eq!(state.marked().collect::<Vec<_>>(), [0]);
+
+        state.action(ListAction::MarkAll);
+        state.set_len(3);
+        assert_eq!(state.marked().collect::<Vec<_>>(), [0, 1, 2]);
+
+        state.action(ListAction::ClearMarks);
+        assert!(!state.is_marked(0));
+    }
+
+    #[test]
+    fn scroll() {
+        let mut state = ListState::new(10).viewport(4);
+
+        state.action(ListAction::Select(5));
+        assert_eq!(state.offset(), 2);
+        state.action(ListAction::Prev(2));
+        assert_eq!(state.offset(), 2);
+        state.action(ListAction::Prev(1));
+        assert_eq!(state.offset(), 2);
+        state.action(ListAction::Prev(1));
+        assert_eq!(state.offset(), 1);
+        state.action(ListAction::PageDown);
+        assert_eq!((state.selected(), state.offset()), (Some(5), 2));
+
+        // The selection follows the view
+        state.action(ListAction::ScrollDown(2));
+        assert_eq!((state.selected(), state.offset()), (Some(5), 4));
+        state.action(ListAction::ScrollDown(10));
+        assert_eq!((state.selected(), state.offset()), (Some(6), 6));
+        state.action(ListAction::ScrollUp(3));
+        assert_eq!((state.selected(), state.offset()), (Some(6), 3));
+
+        let mut state = ListState::new(10)
+            .scroll_padding(1)
+            .viewport(4);
+        state.action(ListAction::Select(3));
+        assert_eq!(state.offset(), 1);
+        state.action(ListAction::Last);
+        assert_eq!(state.offset(), 6);
+        state.action(ListAction::Prev(2));
+        assert_eq!(state.offset(), 6);
+        state.action(ListAction::Prev(1));
+        assert_eq!(state.offset(), 5);
+
+        // Items taller than a row
+        let mut state = ListState::new(10)
+            .item_height(3)
+            .viewport(7);
+        state.action(ListAction::Select(3));
+        assert_eq!(state.offset(), 2);
+    }
+
+    #[cfg(feature = "backend-event")]
+    #[test]
+    fn keys_and_mouse() {
+        use crate::event::{KeyCode, KeyInfo, KeyMod};
+
+        let key = |code: KeyCode| Key(KeyMod::NONE, code);
+        let area = Rect::new(2, 1, 6, 4);
+        let mut state = ListState::new(10).multi_select(true);
+
+        let event = Event::Key(key(KeyCode::Char('j')), KeyCode::Char('j'), KeyInfo::default());
+        assert!(state.handle_event(&event, area));
+        assert_eq!(state.selected(), Some(1));
+        state.handle_keys(key(KeyCode::Down));
+        assert_eq!(state.selected(), Some(2));
+        state.handle_keys(key(KeyCode::Up));
+        state.handle_keys(key(KeyCode::Char('k')));
+        assert_eq!(state.selected(), Some(0));
+
+        state.handle_keys(key(KeyCode::End));
+        assert_eq!((state.selected(), state.offset()), (Some(9), 6));
+        state.handle_keys(key(KeyCode::Home));
+        assert_eq!((state.selected(), state.offset()), (Some(0), 0));
+        state.handle_keys(Key(KeyMod::SHIFT, KeyCode::Char('G')));
+        assert_eq!(state.selected(), Some(9));
+        state.handle_keys(key(KeyCode::Char('g')));
+        assert_eq!(state.selected(), Some(0));
+
+        // The page is the number of items in the area
+        state.handle_keys(key(KeyCode::PageDown));
+        assert_eq!((state.selected(), state.offset()), (Some(4), 1));
+        state.handle_keys(key(KeyCode::PageUp));
+        assert_eq!((state.selected(), state.offset()), (Some(0), 0));
+
+        assert!(state.handle_keys(key(KeyCode::Char(' '))));
+        assert!(state.is_marked(0));
+        assert!(!state.handle_keys(key(KeyCode::Char('x'))));
+        assert!(!state.handle_keys(Key(KeyMod::CTRL, KeyCode::Char('j'))));
+
+        // Click selects the item under the cursor
+        let mouse = |mouse: Mouse| Event::Mouse(mouse, 0, 0);
+        assert!(state.handle_event(&mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 3)), area));
+        assert_eq!(state.selected(), Some(2));
+        assert!(!state.handle_mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 0, 0), area), "Outside of the area");
+        assert!(!state.handle_mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Right, 3, 1), area));
+
+        // The wheel scrolls the view, the selection follows it
+        for _ in 0..3 {
+            state.handle_mouse(Mouse::ScrollDown(KeyMod::NONE, 3, 2), area);
+        }
+        assert_eq!((state.selected(), state.offset()), (Some(3), 3));
+        state.handle_mouse(Mouse::ScrollUp(KeyMod::NONE, 3, 2), area);
+        assert_eq!((state.selected(), state.offset()), (Some(3), 2));
+        assert!(!state.handle_mouse(Mouse::ScrollDown(KeyMod::NONE, 0, 0), area));
+        assert_eq!(state.offset(), 2);
+
+        // Items taller than a row
+        let mut state = ListState::new(10).item_height(2);
+        state.handle_event(&mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 4)), area);
+        assert_eq!(state.selected(), Some(1));
+
+        let mut state = ListState::new(2);
+        assert!(!state.handle_mouse(Mouse::Down(KeyMod::NONE, MouseBtn::Left, 3, 4), area), "Below the last item");
+    }
+
+    #[test]
+    fn draw() {
+        let mut state = ListState::new(4)
+            .multi_select(true)
+            .viewport(3);
+        state.action(ListAction::ToggleMark);
+        state.action(ListAction::Last);
+
+        let mut buf = Buffer::empty(6, 3);
+        SelectList::new(&state, ["a", "b", "c", "d"].map(crate::text::Text::from))
+            .highlight_style(Style::default().bold(true))
+            .highlight_symbol("> ")
+            .marked_symbol("*")
+            .draw(&mut buf, Rect::sized(6, 3));
+
+        let char = |buf: &Buffer, x: u16, y: u16| buf.get((x, y)).and_then(|c| c.char.clone()).unwrap_or(" ".into());
+        let rows: Vec<String> = (0..3)
+            .map(|y| (0..6).map(|x| char(&buf, x, y)).collect())
+            .collect();
+        assert_eq!(rows, ["  b   ", "  c   ", "> d   "]);
+        assert_eq!(buf.get((3, 2)).unwrap().style.bold, Some(true));
+        assert_ne!(buf.get((0, 1)).unwrap().style.bold, Some(true));
+
+        // The list is drawn in a taller rect than the viewport
+        let mut buf = Buffer::empty(6, 4);
+        SelectList::new(&state, ["a", "b", "c", "d"].map(crate::text::Text::from))
+            .marked_symbol("*")
+            .draw(&mut buf, Rect::sized(6, 4));
+        assert_eq!(char(&buf, 0, 0), "*");
+        assert_eq!(char(&buf, 1, 0), "a");
+    }
+}
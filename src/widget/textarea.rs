@@ -0,0 +1,1074 @@
+use crate::{
+    buffer::{Buffer, Cell},
+    layout::{Rect, Wrap},
+    style::{BorderKind, Color, Style},
+};
+
+use super::{Borders, Draw};
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[cfg(feature = "backend-event")]
+use crate::event::{Event, Key, Mouse, MouseBtn};
+
+/// Number of rows scrolled by a mouse wheel step
+#[cfg(feature = "backend-event")]
+const WHEEL_ROWS: usize = 3;
+
+/// Text area message
+/// Same as the prompt actions, but the text has lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextAreaAction {
+    /// Push char into the text
+    Char(char),
+    /// Push string into the text, `\n` starts a new line
+    String(String),
+    /// Split the line at the cursor
+    Newline,
+
+    /// Delete chars to the left, joining lines at the line start
+    DeleteLeft(usize),
+    /// Delete chars to the right, joining lines at the line end
+    DeleteRight(usize),
+    /// Delete next word
+    DeleteNextWord,
+    /// Delete previous word
+    DeletePrevWord,
+    /// Delete all from the cursor pos to the start of the line
+    DeleteToLineStart,
+    /// Delete all from the cursor pos to the end of the line
+    DeleteToLineEnd,
+    /// Delete everything
+    Clear,
+
+    /// Move the cursor left, to the previous line at the line start
+    MoveLeft(usize),
+    /// Move the cursor right, to the next line at the line end
+    MoveRight(usize),
+    /// Move the cursor up by rows, keeping its column
+    MoveUp(usize),
+    /// Move the cursor down by rows, keeping its column
+    MoveDown(usize),
+    /// Scroll and move the cursor up by the viewport height
+    PageUp,
+    /// Scroll and move the cursor down by the viewport height
+    PageDown,
+    /// Move the cursor to the end of a word
+    MoveNextWord,
+    /// Move the cursor to the start of a word
+    MovePrevWord,
+    /// Move the cursor to the start of the line
+    MoveLineStart,
+    /// Move the cursor to the end of the line
+    MoveLineEnd,
+    /// Move the cursor to the start of the text
+    MoveStart,
+    /// Move the cursor to the end of the text
+    MoveEnd,
+    /// Move the cursor to the certain line and position in it
+    MoveTo(usize, usize),
+
+    /// Scroll the view up by rows without moving the cursor
+    ScrollUp(usize),
+    /// Scroll the view down by rows without moving the cursor
+    ScrollDown(usize),
+}
+impl TextAreaAction {
+    /// Whether the action moves the cursor by rows, so it keeps the column
+    fn is_vertical(&self) -> bool {
+        matches!(self, Self::MoveUp(_) | Self::MoveDown(_) | Self::PageUp | Self::PageDown)
+    }
+    /// Whether the action only scrolls the view
+    fn is_scroll(&self) -> bool {
+        matches!(self, Self::ScrollUp(_) | Self::ScrollDown(_))
+    }
+}
+
+/// Visual row, a line or a part of a wrapped line
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    line: usize,
+    /// Graphemes of the line in the row
+    range: Range<usize>,
+    /// Whether the row is the last one of the line
+    last: bool,
+}
+
+/// Text position, line and grapheme index in it
+type Pos = (usize, usize);
+
+/// Text area state
+///
+/// # Notes
+///
+/// - positions in a line are in grapheme clusters, like in the prompt state
+/// - scrolling and wrapping depend on the viewport size, it's updated by [TextAreaState::handle_event]
+///   or [TextAreaState::set_viewport]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextAreaState {
+    /// How to wrap lines longer than the viewport
+    pub wrap: Wrap,
+
+    /// There is always at least one line
+    lines: Vec<String>,
+    /// Cursor line
+    line: usize,
+    /// Cursor position in the line in graphemes
+    cursor: usize,
+    /// Display column kept while moving up and down
+    sticky: Option<usize>,
+    /// First visible row and column
+    scroll: (usize, usize),
+    /// Size of the area the text is drawn in
+    viewport: (u16, u16),
+}
+impl TextAreaState {
+    pub fn new<V: ToString>(value: V) -> Self {
+        let mut state = Self::default();
+        state.set_value(value.to_string());
+        state.move_end();
+        state
+    }
+
+    //
+
+    /// Set how to wrap lines longer than the viewport
+    pub fn wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+    /// Set size of the area the text is drawn in
+    pub fn viewport(mut self, width: u16, height: u16) -> Self {
+        self.set_viewport(width, height);
+        self
+    }
+
+    /// Handle events
+    /// Handles key presses (see [TextAreaState::handle_keys]), pasted text and mouse (see [TextAreaState::handle_mouse])
+    /// `area` is the rect the text is drawn in (see [TextArea::text_rect])
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_event(&mut self, event: &Event, area: Rect) -> bool {
+        self.set_viewport(area.width, area.height);
+
+        match event {
+            Event::Key(key, _, info) if info.is_press() => self.handle_keys(*key),
+            Event::Paste(text) => self.action(TextAreaAction::String(text.clone())),
+            Event::Mouse(mouse, _, _) => self.handle_mouse(*mouse, area),
+            _ => false
+        }
+    }
+    /// Handle mouse events
+    /// Left click places the cursor, the wheel scrolls the view
+    /// `area` is the rect the text is drawn in (see [TextArea::text_rect])
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_mouse(&mut self, mouse: Mouse, area: Rect) -> bool {
+        match mouse {
+            Mouse::Down(_, MouseBtn::Left, x, y) if area.contains((x, y)) => {
+                let rows = self.rows(self.wrap_width(area.width));
+                let row = self.scroll.0 + (y - area.y) as usize;
+                let col = self.scroll.1 + (x - area.x) as usize;
+
+                let (line, cursor) = match rows.get(row) {
+                    Some(row) => (row.line, self.index_in_row(row, col)),
+                    None => (self.lines.len() - 1, self.line_len(self.lines.len() - 1)),
+                };
+                self.action(TextAreaAction::MoveTo(line, cursor))
+            },
+            Mouse::ScrollUp(_, x, y) if area.contains((x, y)) => self.action(TextAreaAction::ScrollUp(WHEEL_ROWS)),
+            Mouse::ScrollDown(_, x, y) if area.contains((x, y)) => self.action(TextAreaAction::ScrollDown(WHEEL_ROWS)),
+            _ => false
+        }
+    }
+
+    /// Handle key events
+    /// Uses the same emacs keymaps as the prompt, `Up`/`Down` and `Ctrl+P`/`Ctrl+N` move by rows
+    /// `Enter` inserts a newline, `Tab` is not handled, so it can move the focus
+    /// Returns whether the state has been updated or not
+    #[cfg(feature = "backend-event")]
+    pub fn handle_keys(&mut self, key: Key) -> bool {
+        use self::TextAreaAction as Action;
+        use crate::event::{KeyCode as C, KeyMod};
+
+        match key {
+            Key(_, C::Enter) => self.action(Action::Newline),
+
+            Key(KeyMod::CTRL, C::Right) => self.action(Action::MoveNextWord),
+            Key(KeyMod::CTRL, C::Left) => self.action(Action::MovePrevWord),
+            Key(KeyMod::CTRL, C::Home) => self.action(Action::MoveStart),
+            Key(KeyMod::CTRL, C::End) => self.action(Action::MoveEnd),
+            Key(KeyMod::ALT, C::Char('f')) => self.action(Action::MoveNextWord),
+            Key(KeyMod::ALT, C::Char('b')) => self.action(Action::MovePrevWord),
+            Key(KeyMod::ALT, C::Char('<')) => self.action(Action::MoveStart),
+            Key(KeyMod::ALT, C::Char('>')) => self.action(Action::MoveEnd),
+            Key(_, C::Left) => self.action(Action::MoveLeft(1)),
+            Key(_, C::Right) => self.action(Action::MoveRight(1)),
+            Key(_, C::Up) => self.action(Action::MoveUp(1)),
+            Key(_, C::Down) => self.action(Action::MoveDown(1)),
+            Key(_, C::PageUp) => self.action(Action::PageUp),
+            Key(_, C::PageDown) => self.action(Action::PageDown),
+            Key(KeyMod::CTRL, C::Char('b')) => self.action(Action::MoveLeft(1)),
+            Key(KeyMod::CTRL, C::Char('f')) => self.action(Action::MoveRight(1)),
+            Key(KeyMod::CTRL, C::Char('p')) => self.action(Action::MoveUp(1)),
+            Key(KeyMod::CTRL, C::Char('n')) => self.action(Action::MoveDown(1)),
+            Key(KeyMod::CTRL, C::Char('a')) => self.action(Action::MoveLineStart),
+            Key(KeyMod::CTRL, C::Char('e')) => self.action(Action::MoveLineEnd),
+            Key(_, C::Home) => self.action(Action::MoveLineStart),
+            Key(_, C::End) => self.action(Action::MoveLineEnd),
+
+            Key(KeyMod::CTRL, C::Char('w')) => self.action(Action::DeletePrevWord),
+            // Same as Ctrl + Backspace
+            Key(KeyMod::CTRL, C::Char('h')) => self.action(Action::DeletePrevWord),
+            Key(KeyMod::ALT, C::Char('d')) => self.action(Action::DeleteNextWord),
+            Key(KeyMod::CTRL, C::Char('u')) => self.action(Action::DeleteToLineStart),
+            Key(KeyMod::CTRL, C::Char('k')) => self.action(Action::DeleteToLineEnd),
+            Key(_, C::Backspace) => self.action(Action::DeleteLeft(1)),
+            Key(_, C::Delete) => self.action(Action::DeleteRight(1)),
+
+            Key(_, C::Char(char)) => self.action(Action::Char(char)),
+
+            _ => false,
+        }
+    }
+
+    /// Send a message to the state
+    /// Returns whether state has been updated or not
+    ///
+    /// # Notes
+    ///
+    /// - the view is scrolled to the cursor after each action, except [TextAreaAction::ScrollUp]
+    ///   and [TextAreaAction::ScrollDown]
+    pub fn action(&mut self, msg: TextAreaAction) -> bool {
+        use self::TextAreaAction as Msg;
+
+        if !msg.is_vertical() {
+            self.sticky = None;
+        }
+        let scroll = msg.is_scroll();
+        let cur = (self.line, self.cursor);
+
+        let updated = match msg {
+            Msg::Char(char) => self.push_char(char),
+            Msg::String(s) => self.push_string(s),
+            Msg::Newline => self.push_char('\n'),
+
+            Msg::DeleteLeft(n) => self.delete(self.pos_left(cur, n), cur),
+            Msg::DeleteRight(n) => self.delete(cur, self.pos_right(cur, n)),
+            Msg::DeleteNextWord => self.delete(cur, self.next_word(cur)),
+            Msg::DeletePrevWord => self.delete(self.prev_word(cur), cur),
+            Msg::DeleteToLineStart => self.delete_to_line_start(),
+            Msg::DeleteToLineEnd => self.delete_to_line_end(),
+            Msg::Clear => self.clear(),
+
+            Msg::MoveLeft(n) => self.move_left(n),
+            Msg::MoveRight(n) => self.move_right(n),
+            Msg::MoveUp(n) => self.move_up(n),
+            Msg::MoveDown(n) => self.move_down(n),
+            Msg::PageUp => self.page_up(),
+            Msg::PageDown => self.page_down(),
+            Msg::MoveNextWord => self.move_next_word(),
+            Msg::MovePrevWord => self.move_prev_word(),
+            Msg::MoveLineStart => self.move_line_start(),
+            Msg::MoveLineEnd => self.move_line_end(),
+            Msg::MoveStart => self.move_start(),
+            Msg::MoveEnd => self.move_end(),
+            Msg::MoveTo(line, pos) => self.move_to(line, pos),
+
+            Msg::ScrollUp(n) => self.scroll_up(n),
+            Msg::ScrollDown(n) => self.scroll_down(n),
+        };
+
+        if !scroll {
+            self.scroll_to_cursor();
+        }
+
+        updated
+    }
+
+    // Pushing
+
+    /// Insert a char at the cursor
+    pub fn push_char(&mut self, char: char) -> bool {
+        self.push_string(char)
+    }
+    /// Insert a string at the cursor
+    /// `\n`, `\r\n` and `\r` start new lines
+    pub fn push_string<S: ToString>(&mut self, s: S) -> bool {
+        let text = s.to_string().replace("\r\n", "\n").replace('\r', "\n");
+        if text.is_empty() {
+            return false;
+        }
+
+        let byte = byte_at(&self.lines[self.line], self.cursor);
+        let tail = self.lines[self.line].split_off(byte);
+        let mut parts = text.split('\n');
+
+        // The first part continues the cursor line, others are new lines
+        self.lines[self.line].push_str(parts.next().unwrap_or_default());
+        for part in parts {
+            self.line += 1;
+            self.lines.insert(self.line, part.to_string());
+        }
+
+        let line = &mut self.lines[self.line];
+        let end = line.len();
+        line.push_str(&tail);
+        self.cursor = index_at_byte(line, end);
+        true
+    }
+
+    // Deleting
+
+    /// Delete text between two positions, the cursor is moved to the start
+    fn delete(&mut self, start: Pos, end: Pos) -> bool {
+        if start == end {
+            return false;
+        }
+
+        let end_byte = byte_at(&self.lines[end.0], end.1);
+        let tail = self.lines[end.0].split_off(end_byte);
+        let start_byte = byte_at(&self.lines[start.0], start.1);
+
+        self.lines[start.0].truncate(start_byte);
+        self.lines[start.0].push_str(&tail);
+        self.lines.drain(start.0 + 1..=end.0);
+
+        self.line = start.0;
+        self.cursor = index_at_byte(&self.lines[start.0], start_byte);
+        true
+    }
+    pub fn delete_left(&mut self, amount: usize) -> bool {
+        let cur = (self.line, self.cursor);
+        self.delete(self.pos_left(cur, amount), cur)
+    }
+    pub fn delete_right(&mut self, amount: usize) -> bool {
+        let cur = (self.line, self.cursor);
+        self.delete(cur, self.pos_right(cur, amount))
+    }
+    /// Delete to the line start, or the previous newline at the line start
+    pub fn delete_to_line_start(&mut self) -> bool {
+        match self.cursor {
+            0 => self.delete_left(1),
+            cursor => self.delete((self.line, 0), (self.line, cursor)),
+        }
+    }
+    /// Delete to the line end, or the next newline at the line end
+    pub fn delete_to_line_end(&mut self) -> bool {
+        let len = self.line_len(self.line);
+        match self.cursor {
+            cursor if cursor >= len => self.delete_right(1),
+            cursor => self.delete((self.line, cursor), (self.line, len)),
+        }
+    }
+    pub fn clear(&mut self) -> bool {
+        self.lines = vec![String::new()];
+        self.line = 0;
+        self.cursor = 0;
+        true
+    }
+
+    // Move cursor
+
+    pub fn move_start(&mut self) -> bool {
+        self.move_to(0, 0)
+    }
+    pub fn move_end(&mut self) -> bool {
+        self.move_to(self.lines.len() - 1, usize::MAX)
+    }
+    pub fn move_line_start(&mut self) -> bool {
+        self.move_to(self.line, 0)
+    }
+    pub fn move_line_end(&mut self) -> bool {
+        self.move_to(self.line, usize::MAX)
+    }
+    pub fn move_next_word(&mut self) -> bool {
+        let (line, pos) = self.next_word((self.line, self.cursor));
+        self.move_to(line, pos)
+    }
+    pub fn move_prev_word(&mut self) -> bool {
+        let (line, pos) = self.prev_word((self.line, self.cursor));
+        self.move_to(line, pos)
+    }
+    pub fn move_left(&mut self, amount: usize) -> bool {
+        let (line, pos) = self.pos_left((self.line, self.cursor), amount);
+        self.move_to(line, pos)
+    }
+    pub fn move_right(&mut self, amount: usize) -> bool {
+        let (line, pos) = self.pos_right((self.line, self.cursor), amount);
+        self.move_to(line, pos)
+    }
+    /// Move the cursor up by visual rows
+    /// Returns `false` if the cursor is on the first row
+    pub fn move_up(&mut self, amount: usize) -> bool {
+        self.move_rows(-(amount as isize))
+    }
+    /// Move the cursor down by visual rows
+    /// Returns `false` if the cursor is on the last row
+    pub fn move_down(&mut self, amount: usize) -> bool {
+        self.move_rows(amount as isize)
+    }
+    pub fn page_up(&mut self) -> bool {
+        let height = self.viewport.1.max(1) as usize;
+        self.scroll_up(height);
+        self.move_up(height)
+    }
+    pub fn page_down(&mut self) -> bool {
+        let height = self.viewport.1.max(1) as usize;
+        self.scroll_down(height);
+        self.move_down(height)
+    }
+    /// Move the cursor to `pos` in `line`
+    /// Both are clamped to the text
+    pub fn move_to(&mut self, line: usize, pos: usize) -> bool {
+        self.line = line.min(self.lines.len() - 1);
+        self.cursor = pos.min(self.line_len(self.line));
+        true
+    }
+    fn move_rows(&mut self, delta: isize) -> bool {
+        let rows = self.rows(self.wrap_width(self.viewport.0));
+        let (row, col) = self.visual_pos(&rows);
+        let col = *self.sticky.get_or_insert(col);
+
+        let target = row.saturating_add_signed(delta).min(rows.len() - 1);
+        if target == row {
+            return false;
+        }
+
+        let row = &rows[target];
+        self.line = row.line;
+        self.cursor = self.index_in_row(row, col);
+        true
+    }
+
+    // Scroll
+
+    /// Set size of the area the text is drawn in and scroll to the cursor
+    pub fn set_viewport(&mut self, width: u16, height: u16) {
+        self.viewport = (width, height);
+        self.scroll_to_cursor();
+    }
+    pub fn scroll_up(&mut self, amount: usize) -> bool {
+        let row = self.scroll.0.saturating_sub(amount);
+        let updated = row != self.scroll.0;
+        self.scroll.0 = row;
+        updated
+    }
+    pub fn scroll_down(&mut self, amount: usize) -> bool {
+        let rows = self.rows(self.wrap_width(self.viewport.0)).len();
+        let row = self.scroll.0.saturating_add(amount).min(rows - 1);
+        let updated = row != self.scroll.0;
+        self.scroll.0 = row;
+        updated
+    }
+    /// Scroll the view, so the cursor is visible
+    /// Does nothing if the viewport size is unknown
+    pub fn scroll_to_cursor(&mut self) {
+        let (width, height) = (self.viewport.0 as usize, self.viewport.1 as usize);
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let wrap_width = self.wrap_width(self.viewport.0);
+        let rows = self.rows(wrap_width);
+        let (row, col) = self.visual_pos(&rows);
+
+        self.scroll.0 = self.scroll.0
+            .min(row)
+            .max((row + 1).saturating_sub(height));
+        self.scroll.1 = match wrap_width {
+            // Wrapped rows fit the viewport
+            0 => self.scroll.1.min(col).max((col + 1).saturating_sub(width)),
+            _ => 0,
+        };
+    }
+
+    // Rows
+
+    /// Width to wrap lines at, `0` if lines are not wrapped
+    fn wrap_width(&self, width: u16) -> usize {
+        match self.wrap {
+            Wrap::None => 0,
+            _ => width as usize,
+        }
+    }
+    /// Visual rows of the text wrapped at `width`
+    fn rows(&self, width: usize) -> Vec<Row> {
+        let mut rows = vec![];
+
+        for (line, text) in self.lines.iter().enumerate() {
+            let ranges = self.wrap_line(text, width);
+            let count = ranges.len();
+
+            rows.extend(ranges.into_iter().enumerate().map(|(i, range)| Row {
+                line,
+                range,
+                last: i + 1 == count,
+            }));
+        }
+
+        rows
+    }
+    /// Grapheme ranges of the line parts wrapped at `width`, the line is not wrapped if it's `0`
+    /// Spaces can hang over the edge, so words are not moved because of them
+    fn wrap_line(&self, line: &str, width: usize) -> Vec<Range<usize>> {
+        let graphemes: Vec<(bool, usize)> = line
+            .graphemes(true)
+            .map(|g| (g.chars().all(char::is_whitespace), g.width()))
+            .collect();
+
+        let mut ranges = vec![];
+        let mut start = 0usize;
+        let mut col = 0usize;
+        // Index after the last space in the row
+        let mut space: Option<usize> = None;
+
+        for (i, &(is_space, g_width)) in graphemes.iter().enumerate() {
+            if width > 0 && (!is_space || self.wrap == Wrap::Break) && col + g_width > width && i > start {
+                let brk = match (self.wrap, space) {
+                    (Wrap::Words | Wrap::BreakWords, Some(space)) => Some(space),
+                    // A long word overflows
+                    (Wrap::Words, None) => None,
+                    _ => Some(i),
+                };
+
+                if let Some(brk) = brk {
+                    ranges.push(start..brk);
+                    col = graphemes[brk..i].iter().map(|g| g.1).sum();
+                    start = brk;
+                    space = None;
+                }
+            }
+
+            col += g_width;
+            if is_space {
+                space = Some(i + 1);
+            }
+        }
+
+        ranges.push(start..graphemes.len());
+        ranges
+    }
+    /// Visual row and column of the cursor
+    fn visual_pos(&self, rows: &[Row]) -> (usize, usize) {
+        let row = rows
+            .partition_point(|r| r.line < self.line || r.line == self.line && r.range.start <= self.cursor)
+            .saturating_sub(1);
+        let start = rows.get(row).map_or(0, |r| r.range.start);
+        let col = self.lines[self.line]
+            .graphemes(true)
+            .skip(start)
+            .take(self.cursor - start)
+            .map(|g| g.width())
+            .sum();
+
+        (row, col)
+    }
+    /// Index in the line of the grapheme at the display column `col` of the row
+    /// The right half of a wide grapheme belongs to the position after it
+    fn index_in_row(&self, row: &Row, col: usize) -> usize {
+        let mut cur_col = 0usize;
+        let graphemes = self.lines[row.line]
+            .graphemes(true)
+            .skip(row.range.start)
+            .take(row.range.len());
+
+        for (i, g) in (row.range.start..).zip(graphemes) {
+            let width = g.width();
+            if col < cur_col + width.div_ceil(2) {
+                return i;
+            }
+            cur_col += width;
+        }
+
+        // The end of a wrapped row is the start of the next one
+        if row.last || row.range.is_empty() {
+            row.range.end
+        } else {
+            row.range.end - 1
+        }
+    }
+
+    // Positions
+
+    /// Position `amount` graphemes to the left, a newline counts as one
+    fn pos_left(&self, (mut line, mut pos): Pos, mut amount: usize) -> Pos {
+        loop {
+            let step = amount.min(pos);
+            pos -= step;
+            amount -= step;
+
+            if amount == 0 || line == 0 {
+                return (line, pos);
+            }
+            line -= 1;
+            pos = self.line_len(line);
+            amount -= 1;
+        }
+    }
+    /// Position `amount` graphemes to the right, a newline counts as one
+    fn pos_right(&self, (mut line, mut pos): Pos, mut amount: usize) -> Pos {
+        loop {
+            let len = self.line_len(line);
+            let step = amount.min(len - pos.min(len));
+            pos = pos.min(len) + step;
+            amount -= step;
+
+            if amount == 0 || line + 1 >= self.lines.len() {
+                return (line, pos);
+            }
+            line += 1;
+            pos = 0;
+            amount -= 1;
+        }
+    }
+    /// End of the word after `pos`, newlines separate words
+    fn next_word(&self, (mut line, mut pos): Pos) -> Pos {
+        let mut was_word = false;
+
+        loop {
+            for g in self.lines[line].graphemes(true).skip(pos) {
+                if is_separator(g) {
+                    if was_word {
+                        return (line, pos);
+                    }
+                } else {
+                    was_word = true;
+                }
+                pos += 1;
+            }
+
+            if was_word || line + 1 >= self.lines.len() {
+                return (line, pos);
+            }
+            line += 1;
+            pos = 0;
+        }
+    }
+    /// Start of the word before `pos`, newlines separate words
+    fn prev_word(&self, (mut line, mut pos): Pos) -> Pos {
+        let mut was_word = false;
+
+        loop {
+            let graphemes: Vec<&str> = self.lines[line].graphemes(true).take(pos).collect();
+            for g in graphemes.into_iter().rev() {
+                if is_separator(g) {
+                    if was_word {
+                        return (line, pos);
+                    }
+                } else {
+                    was_word = true;
+                }
+                pos -= 1;
+            }
+
+            if was_word || line == 0 {
+                return (line, pos);
+            }
+            line -= 1;
+            pos = self.line_len(line);
+        }
+    }
+
+    // Get
+
+    /// Line length in graphemes
+    pub fn line_len(&self, line: usize) -> usize {
+        self.lines.get(line).map_or(0, |l| l.graphemes(true).count())
+    }
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    /// Text area value, lines are joined with `\n`
+    pub fn value(&self) -> String {
+        self.lines.join("\n")
+    }
+    /// Replace the value
+    /// The cursor is clamped to the new text
+    pub fn set_value(&mut self, value: String) {
+        self.lines = value
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .split('\n')
+            .map(str::to_string)
+            .collect();
+        self.sticky = None;
+        self.move_to(self.line, self.cursor);
+        self.scroll_to_cursor();
+    }
+    /// Cursor line and position in it in graphemes
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.line, self.cursor)
+    }
+    /// Cursor visual row and display column, when the text is drawn `width` columns wide
+    /// The row counts wrapped rows, the column starts at the row start
+    pub fn cursor_pos(&self, width: u16) -> (usize, usize) {
+        self.visual_pos(&self.rows(self.wrap_width(width)))
+    }
+    /// Number of visual rows, when the text is drawn `width` columns wide
+    pub fn row_count(&self, width: u16) -> usize {
+        self.rows(self.wrap_width(width)).len()
+    }
+    /// First visible row and column
+    pub fn scroll(&self) -> (usize, usize) {
+        self.scroll
+    }
+    pub fn viewport_size(&self) -> (u16, u16) {
+        self.viewport
+    }
+}
+impl Default for TextAreaState {
+    fn default() -> Self {
+        Self {
+            wrap: Wrap::None,
+            lines: vec![String::new()],
+            line: 0,
+            cursor: 0,
+            sticky: None,
+            scroll: (0, 0),
+            viewport: (0, 0),
+        }
+    }
+}
+
+fn is_separator(g: &str) -> bool {
+    g.chars().all(char::is_whitespace)
+}
+/// Byte offset of the grapheme at `index`
+fn byte_at(s: &str, index: usize) -> usize {
+    s.grapheme_indices(true)
+        .nth(index)
+        .map_or(s.len(), |(i, _)| i)
+}
+/// Index of the grapheme that starts at or after `byte`
+fn index_at_byte(s: &str, byte: usize) -> usize {
+    s.grapheme_indices(true)
+        .take_while(|(i, _)| *i < byte)
+        .count()
+}
+
+/// Text area widget
+/// Multi-line [Prompt](super::Prompt)
+///
+/// # Examples
+///
+/// ```
+/// # use tuich::{buffer::Buffer, layout::{Rect, Wrap}, widget::{Draw, textarea::*}};
+/// let mut state = TextAreaState::new("Hello,\nworld!")
+///     .wrap(Wrap::Words)
+///     .viewport(20, 5);
+/// state.action(TextAreaAction::MoveUp(1));
+/// assert_eq!(state.cursor(), (0, 6));
+///
+/// let mut buf = Buffer::empty(20, 5);
+/// TextArea::new(&state).draw(&mut buf, Rect::sized(20, 5));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextArea<'a> {
+    pub state: &'a TextAreaState,
+    pub style: Style,
+    pub cursor_style: Style,
+    /// Text shown when the value is empty
+    pub placeholder: Option<String>,
+    pub placeholder_style: Style,
+    pub focused: bool,
+    pub borders: Option<Borders>
+}
+impl<'a> TextArea<'a> {
+    pub fn new(state: &'a TextAreaState) -> Self {
+        Self {
+            state,
+            style: Style::default(),
+            cursor_style: Style::new(Color::Black, Color::LightGray),
+            placeholder: None,
+            placeholder_style: Color::LightBlack.into(),
+            focused: true,
+            borders: None,
+        }
+    }
+
+    //
+
+    /// Set text style
+    pub fn style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.style = style.into();
+        self
+    }
+    /// Set cursor style
+    pub fn cursor_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.cursor_style = style.into();
+        self
+    }
+    /// Set text shown when the value is empty
+    pub fn placeholder<S: ToString>(mut self, text: S) -> Self {
+        self.placeholder = Some(text.to_string());
+        self
+    }
+    /// Set placeholder text style
+    pub fn placeholder_style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.placeholder_style = style.into();
+        self
+    }
+    /// Set whether the cursor is visible or not
+    pub fn focused(mut self, value: bool) -> Self {
+        self.focused = value;
+        self
+    }
+    /// Set border kind
+    pub fn border_kind(mut self, kind: BorderKind) -> Self {
+        if let Some(borders) = self.borders {
+            self.borders = Some(borders.kind(kind));
+        } else {
+            self.borders = Some(Borders::new(kind));
+        }
+
+        self
+    }
+    /// Set border style
+    pub fn border_style<S: Into<Style>>(mut self, style: S) -> Self {
+        if let Some(borders) = self.borders {
+            self.borders = Some(borders.style(style));
+        } else {
+            self.borders = Some(Borders::single().style(style));
+        }
+
+        self
+    }
+    /// Set a cell to fill the background
+    pub fn fill<C: Into<Cell>>(mut self, cell: C) -> Self {
+        if let Some(borders) = self.borders {
+            self.borders = Some(borders.fill(cell));
+        } else {
+            self.borders = Some(Borders::single().fill(cell));
+        }
+
+        self
+    }
+
+    /// Returns the rect the text is drawn in when the text area is drawn in `rect`
+    /// Pass it to [TextAreaState::handle_event]
+    pub fn text_rect(&self, rect: Rect) -> Rect {
+        if self.borders.is_some() {
+            rect.margin(1)
+        } else {
+            rect
+        }
+    }
+}
+
+impl<'a> Draw for TextArea<'a> {
+    fn draw(self, buf: &mut Buffer, rect: Rect) -> Rect {
+        let text_rect = self.text_rect(rect);
+        let state = self.state;
+
+        if let Some(borders) = self.borders {
+            borders.draw(buf, rect);
+        }
+
+        let width = text_rect.width as usize;
+        let rows = state.rows(state.wrap_width(text_rect.width));
+        let (scroll_row, scroll_col) = state.scroll;
+        let x = text_rect.x;
+
+        // Draw placeholder
+        if let Some(placeholder) = self.placeholder.as_ref().filter(|_| state.is_empty()) {
+            buf.set_clamped_string(text_rect.pos(), 0, placeholder, self.placeholder_style, text_rect.width);
+        }
+
+        // Draw text
+        for (y, row) in (text_rect.y..text_rect.bottom()).zip(rows.iter().skip(scroll_row)) {
+            let graphemes = state.lines[row.line]
+                .graphemes(true)
+                .skip(row.range.start)
+                .take(row.range.len());
+            let mut col = 0usize;
+
+            for g in graphemes {
+                let g_width = g.width();
+                let start = col;
+                col += g_width;
+
+                if col <= scroll_col || g_width == 0 {
+                    continue;
+                }
+                if col - scroll_col > width {
+                    break;
+                }
+
+                if start < scroll_col {
+                    // Wide grapheme cut by the left edge
+                    for cut_x in 0..col - scroll_col {
+                        buf.set((x + cut_x as u16, y), Some(" "), self.style);
+                    }
+                } else {
+                    buf.set((x + (start - scroll_col) as u16, y), Some(g), self.style);
+                }
+            }
+        }
+
+        // Draw cursor
+        if self.focused && width > 0 {
+            let (row, col) = state.visual_pos(&rows);
+
+            if (scroll_row..scroll_row + text_rect.height as usize).contains(&row) {
+                // Spaces hanging over the edge of wrapped rows keep the cursor at the edge
+                let cur_x = col.saturating_sub(scroll_col).min(width - 1) as u16;
+                let cur_y = (row - scroll_row) as u16;
+
+                buf.set_style((x + cur_x, text_rect.y + cur_y), self.cursor_style);
+            }
+        }
+
+        rect
+    }
+}
+
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_texts(state: &TextAreaState, width: usize) -> Vec<String> {
+        state
+            .rows(width)
+            .iter()
+            .map(|row| {
+                state.lines[row.line]
+                    .graphemes(true)
+                    .skip(row.range.start)
+                    .take(row.range.len())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn editing() {
+        let mut state = TextAreaState::new("ab");
+
+        state.action(TextAreaAction::MoveLeft(1));
+        state.action(TextAreaAction::Newline);
+        assert_eq!(state.lines(), ["a", "b"]);
+        assert_eq!(state.cursor(), (1, 0));
+
+        state.action(TextAreaAction::String("1\r\n2\n".into()));
+        assert_eq!(state.value(), "a\n1\n2\nb");
+        assert_eq!(state.cursor(), (3, 0));
+
+        state.action(TextAreaAction::DeleteLeft(3));
+        assert_eq!(state.value(), "a\n1b");
+        assert_eq!(state.cursor(), (1, 1));
+
+        state.action(TextAreaAction::DeleteToLineStart);
+        state.action(TextAreaAction::DeleteToLineStart);
+        assert_eq!(state.value(), "ab");
+
+        state.action(TextAreaAction::MoveLineEnd);
+        state.action(TextAreaAction::DeleteRight(1));
+        assert_eq!(state.value(), "ab", "Nothing to delete");
+    }
+
+    #[test]
+    fn words() {
+        let mut state = TextAreaState::new("one two\n  three");
+        state.action(TextAreaAction::MoveStart);
+
+        state.action(TextAreaAction::MoveNextWord);
+        assert_eq!(state.cursor(), (0, 3));
+        state.action(TextAreaAction::MoveNextWord);
+        state.action(TextAreaAction::MoveNextWord);
+        assert_eq!(state.cursor(), (1, 7));
+
+        state.action(TextAreaAction::DeletePrevWord);
+        assert_eq!(state.value(), "one two\n  ");
+        state.action(TextAreaAction::DeletePrevWord);
+        assert_eq!(state.value(), "one ");
+    }
+
+    #[test]
+    fn sticky_column() {
+        let mut state = TextAreaState::new("long line\nab\n日本語です");
+        state.action(TextAreaAction::MoveTo(0, 7));
+
+        state.action(TextAreaAction::MoveDown(1));
+        assert_eq!(state.cursor(), (1, 2));
+        state.action(TextAreaAction::MoveDown(1));
+        assert_eq!(state.cursor(), (2, 4), "Column 7 is the right half of で");
+        state.action(TextAreaAction::MoveUp(2));
+        assert_eq!(state.cursor(), (0, 7));
+
+        state.action(TextAreaAction::MoveLeft(1));
+        state.action(TextAreaAction::MoveDown(1));
+        assert_eq!(state.cursor(), (1, 2));
+        assert!(!state.move_up(0), "Same row");
+    }
+
+    #[test]
+    fn wrap() {
+        let state = TextAreaState::new("the quick brown\nfox").wrap(Wrap::Words);
+        assert_eq!(row_texts(&state, 6), ["the ", "quick ", "brown", "fox"]);
+
+        let state = state.wrap(Wrap::Break);
+        assert_eq!(row_texts(&state, 6), ["the qu", "ick br", "own", "fox"]);
+
+        let state = TextAreaState::new("abcdefgh ij").wrap(Wrap::Words);
+        assert_eq!(row_texts(&state, 4), ["abcdefgh ", "ij"]);
+        let state = state.wrap(Wrap::BreakWords);
+        assert_eq!(row_texts(&state, 4), ["abcd", "efgh ", "ij"]);
+
+        // Moving by wrapped rows
+        let mut state = TextAreaState::new("the quick brown").wrap(Wrap::Words).viewport(6, 2);
+        state.action(TextAreaAction::MoveTo(0, 1));
+        state.action(TextAreaAction::MoveDown(1));
+        assert_eq!(state.cursor(), (0, 5));
+        state.action(TextAreaAction::MoveLineEnd);
+        state.action(TextAreaAction::MoveUp(1));
+        assert_eq!(state.cursor(), (0, 9), "Space hanging over the edge");
+    }
+
+    #[test]
+    fn scroll() {
+        let text: Vec<String> = (0..10).map(|i| format!("line {i}")).collect();
+        let mut state = TextAreaState::new(text.join("\n")).viewport(4, 3);
+        assert_eq!(state.scroll(), (7, 3));
+
+        state.action(TextAreaAction::PageUp);
+        assert_eq!(state.cursor(), (6, 6));
+        assert_eq!(state.scroll(), (4, 3));
+
+        state.action(TextAreaAction::MoveLineStart);
+        state.action(TextAreaAction::ScrollDown(20));
+        assert_eq!(state.scroll(), (9, 0));
+        assert_eq!(state.cursor(), (6, 0), "Scrolling keeps the cursor");
+
+        state.action(TextAreaAction::PageDown);
+        assert_eq!(state.cursor(), (9, 0));
+        assert_eq!(state.scroll(), (9, 0));
+    }
+
+    #[test]
+    fn draw() {
+        let mut state = TextAreaState::new("a\nhello world\nb").wrap(Wrap::Words).viewport(6, 2);
+        state.action(TextAreaAction::MoveTo(1, 1));
+        state.action(TextAreaAction::MoveDown(1));
+        assert_eq!(state.cursor(), (1, 7));
+        assert_eq!(state.scroll(), (1, 0));
+
+        let mut buf = Buffer::empty(6, 2);
+        TextArea::new(&state).draw(&mut buf, Rect::sized(6, 2));
+
+        let char = |x: u16, y: u16| buf.get((x, y)).and_then(|c| c.char.clone()).unwrap_or_default();
+        assert_eq!(char(0, 0), "h");
+        assert_eq!(char(4, 0), "o");
+        assert_eq!(char(0, 1), "w");
+        assert_eq!(buf.get((1, 1)).unwrap().style, Style::new(Color::Black, Color::LightGray));
+    }
+}